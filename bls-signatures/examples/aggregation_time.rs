@@ -1,8 +1,8 @@
 use std::time::{Duration, Instant};
 
 use bls_signatures::{
-    key::{PrivateKey, PublicKey, Serialize},
-    sig_aggregate::{aggregate, hash, verify, verify_messages, Signature}
+    key::{PrivateKey, Serialize, ValidatedPublicKey},
+    sig_aggregate::{aggregate, hash, verify, verify_messages, Signature, ValidatedSignature}
  };
 
 #[cfg(feature = "pairing")]
@@ -102,17 +102,17 @@ fn run_different_messages (num_messages: usize) {
             .map(|message| hash(message))
             .collect::<Vec<_>>();
     });
-    let public_keys: Vec<PublicKey>;
+    let public_keys: Vec<ValidatedPublicKey>;
     measure!("extracting public keys", num_messages, {
         public_keys = private_keys
             .par_iter()
-            .map(|pk| pk.public_key())
+            .map(|pk| ValidatedPublicKey::new_unchecked(pk.public_key()))
             .collect::<Vec<_>>();
     });
 
-    let agg_sig: Signature;
+    let agg_sig: ValidatedSignature;
     measure!("deserialize signature", {
-        agg_sig = Signature::from_bytes(&serialized_signature).unwrap();
+        agg_sig = ValidatedSignature::from_bytes(&serialized_signature).unwrap();
     });
 
     measure!("verification", num_messages, {
@@ -170,17 +170,17 @@ fn run_same_message(num_messages: usize) {
     measure!("hashing messages", num_messages, {
          hashes = (0..num_messages).map(|_| hash(&message)).collect();
     });
-    let public_keys: Vec<PublicKey>;
+    let public_keys: Vec<ValidatedPublicKey>;
     measure!("extracting public keys", num_messages, {
         public_keys = private_keys
             .par_iter()
-            .map(|pk| pk.public_key())
+            .map(|pk| ValidatedPublicKey::new_unchecked(pk.public_key()))
             .collect::<Vec<_>>();
     });
 
-    let agg_sig: Signature;
+    let agg_sig: ValidatedSignature;
     measure!("deserialize signature", {
-        agg_sig = Signature::from_bytes(&serialized_signature).unwrap();
+        agg_sig = ValidatedSignature::from_bytes(&serialized_signature).unwrap();
     });
 
     measure!("verification", num_messages, {
@@ -0,0 +1,231 @@
+//! Rogue-key resistant aggregation of signatures on the same message, following
+//! Boneh, Drijvers and Neven: https://eprint.iacr.org/2018/483.pdf
+//!
+//! Signers sign as usual. Every public key `pk_i` in a set gets a coefficient
+//! `t_i = H(pk_i, {pk_1, ..., pk_n})` and both the keys and the signatures are aggregated
+//! as `\sum_{i = 0}^n t_i * x_i`. No proof of possession is needed.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "multicore")]
+use rayon::prelude::*;
+
+#[cfg(feature = "pairing")]
+use bls12_381::{G1Projective, G2Projective, Scalar};
+#[cfg(feature = "blst")]
+use blstrs::{G1Projective, G2Projective, Scalar};
+#[cfg(feature = "blst")]
+use group::Group;
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+use crate::key::{PublicKey, Serialize, ValidatedPublicKey};
+use crate::sig_aggregate::{scalar_from_u128_bytes, Signature, ValidatedSignature};
+
+const COEFF_DST: &[u8] = b"BLS_BDN_COEFF_BLS12381G1_XMD:SHA-256_";
+
+/// Public key aggregated from a set of keys with their BDN coefficients.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AggregatePublicKey(G1Projective);
+
+impl From<AggregatePublicKey> for PublicKey {
+    fn from(val: AggregatePublicKey) -> Self {
+        PublicKey(val.0)
+    }
+}
+
+impl AggregatePublicKey {
+    /// Aggregates the given public keys.
+    /// Calculated by `pk = \sum_{i = 0}^n t_i * pk_i`.
+    pub fn from_keys(public_keys: &[ValidatedPublicKey]) -> Result<Self, Error> {
+        let coefficients = coefficients(public_keys)?;
+
+        #[cfg(feature = "multicore")]
+        let res = public_keys
+            .par_iter()
+            .zip(coefficients.par_iter())
+            .fold(G1Projective::identity, |acc, (pk, t)| acc + pk.0 * t)
+            .reduce(G1Projective::identity, |acc, val| acc + val);
+
+        #[cfg(not(feature = "multicore"))]
+        let res = public_keys
+            .iter()
+            .zip(coefficients.iter())
+            .fold(G1Projective::identity(), |acc, (pk, t)| acc + pk.0 * t);
+
+        Ok(AggregatePublicKey(res))
+    }
+
+    /// Verifies a signature produced by `aggregate_weighted` over the same keys.
+    pub fn verify<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> bool {
+        PublicKey::from(*self).verify(sig, message)
+    }
+}
+
+/// Aggregates signatures on the same message, where `signatures[i]` was produced by the
+/// owner of `public_keys[i]`.
+/// Calculated by `signature = \sum_{i = 0}^n t_i * signature_i`.
+pub fn aggregate_weighted(
+    signatures: &[ValidatedSignature],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<Signature, Error> {
+    if signatures.len() != public_keys.len() {
+        return Err(Error::SizeMismatch);
+    }
+
+    let coefficients = coefficients(public_keys)?;
+
+    #[cfg(feature = "multicore")]
+    let res = signatures
+        .par_iter()
+        .zip(coefficients.par_iter())
+        .fold(G2Projective::identity, |acc, (sig, t)| {
+            acc + G2Projective::from(**sig) * t
+        })
+        .reduce(G2Projective::identity, |acc, val| acc + val);
+
+    #[cfg(not(feature = "multicore"))]
+    let res = signatures
+        .iter()
+        .zip(coefficients.iter())
+        .fold(G2Projective::identity(), |acc, (sig, t)| {
+            acc + G2Projective::from(**sig) * t
+        });
+
+    Ok(res.into())
+}
+
+/// Computes the 128-bit coefficient `t_i = H(pk_i, H(pk_1 || ... || pk_n))` of every key.
+/// The coefficients depend on the order of the keys.
+fn coefficients(public_keys: &[ValidatedPublicKey]) -> Result<Vec<Scalar>, Error> {
+    if public_keys.is_empty() {
+        return Err(Error::ZeroSizedInput);
+    }
+
+    if public_keys.iter().any(|pk| pk.0.is_identity().into()) {
+        return Err(Error::InvalidPublicKey);
+    }
+
+    let encoded: Vec<_> = public_keys.iter().map(|pk| pk.as_bytes()).collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(COEFF_DST);
+    for pk in &encoded {
+        hasher.update(pk);
+    }
+    let keys_digest = hasher.finalize();
+
+    let coefficients = encoded
+        .iter()
+        .map(|pk| {
+            let digest = Sha256::new()
+                .chain(COEFF_DST)
+                .chain(keys_digest)
+                .chain(pk)
+                .finalize();
+
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&digest[..16]);
+            scalar_from_u128_bytes(&bytes)
+        })
+        .collect();
+
+    Ok(coefficients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::key::PrivateKey;
+    use crate::sig_aggregate::{aggregate, fast_aggregate_verify};
+
+    #[test]
+    fn bdn_aggregation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let num_keys = 10;
+
+        // generate private keys
+        let private_keys: Vec<_> = (0..num_keys)
+            .map(|_| PrivateKey::generate(&mut rng))
+            .collect();
+
+        // generate message
+        let message: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+
+        // sign message
+        let sigs = private_keys
+            .iter()
+            .map(|pk| pk.sign(&message).validate().unwrap())
+            .collect::<Vec<_>>();
+
+        let public_keys = private_keys
+            .iter()
+            .map(|pk| pk.public_key().validate().unwrap())
+            .collect::<Vec<_>>();
+
+        let aggregated_signature = aggregate_weighted(&sigs, &public_keys).unwrap();
+        let aggregated_key = AggregatePublicKey::from_keys(&public_keys).unwrap();
+
+        assert!(aggregated_key.verify(aggregated_signature, &message));
+        assert!(!aggregated_key.verify(aggregated_signature, b"other message"));
+
+        // plain aggregation does not verify against the weighted key, and vice versa
+        let plain_sigs: Vec<Signature> = sigs.iter().map(|sig| **sig).collect();
+        let plain_signature = aggregate(&plain_sigs).unwrap();
+        assert!(!aggregated_key.verify(plain_signature, &message));
+        assert!(!fast_aggregate_verify(
+            &aggregated_signature.validate().unwrap(),
+            &message,
+            &public_keys
+        ));
+
+        // coefficients are bound to the whole set of keys
+        let partial_key = AggregatePublicKey::from_keys(&public_keys[1..]).unwrap();
+        let partial_signature = aggregate_weighted(&sigs[1..], &public_keys[1..]).unwrap();
+        assert!(partial_key.verify(partial_signature, &message));
+        assert!(!partial_key.verify(aggregated_signature, &message));
+    }
+
+    #[test]
+    fn rogue_key() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let honest = PrivateKey::generate(&mut rng);
+        let attacker = PrivateKey::generate(&mut rng);
+        let message = b"hello";
+
+        // pk_rogue = g1 * sk_attacker - pk_honest, so that the plain sum is g1 * sk_attacker
+        let rogue = PublicKey(attacker.public_key().0 - honest.public_key().0);
+        let keys = [
+            honest.public_key().validate().unwrap(),
+            rogue.validate().unwrap(),
+        ];
+        let forged = attacker.sign(message);
+
+        let plain_key = PublicKey(keys[0].0 + keys[1].0);
+        assert!(plain_key.verify(forged, message));
+
+        let aggregated_key = AggregatePublicKey::from_keys(&keys).unwrap();
+        assert!(!aggregated_key.verify(forged, message));
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+        let sig = sk.sign(b"hello").validate().unwrap();
+        let pk = sk.public_key().validate().unwrap();
+
+        assert!(AggregatePublicKey::from_keys(&[]).is_err());
+        assert!(aggregate_weighted(&[], &[]).is_err());
+        assert!(aggregate_weighted(&[sig, sig], &[pk]).is_err());
+
+        let zero_key = ValidatedPublicKey::new_unchecked(PublicKey(G1Projective::identity()));
+        assert!(AggregatePublicKey::from_keys(&[pk, zero_key]).is_err());
+    }
+}
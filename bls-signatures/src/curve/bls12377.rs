@@ -1,10 +1,11 @@
+use super::compressed::{deserialize_group, serialize_group};
 use crate::group::PrimeOrder;
 use crate::group::{self, Element, PairingCurve as PC, Point, Scalar as Sc};
 use ark_bls12_377 as bls377;
-use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ec::{PairingEngine, ProjectiveCurve};
 use ark_ff::PrimeField;
 use ark_ff::{Field, One, UniformRand, Zero};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::CanonicalSerialize;
 use bls_crypto::{
     hash_to_curve::{try_and_increment::TryAndIncrement, HashToCurve},
     hashers::DirectHasher,
@@ -34,6 +35,7 @@ pub enum ZexeError {
 
 // TODO(gakonst): Make this work with any PairingEngine.
 
+/// Serialized as 32 little-endian bytes, the arkworks encoding, like the BLS12-381 scalar.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Scalar(
     #[serde(deserialize_with = "deserialize_field")]
@@ -161,6 +163,10 @@ impl Point for G1 {
 
         Ok(())
     }
+
+    fn negate(&mut self) {
+        self.0 = -self.0;
+    }
 }
 
 impl fmt::Display for G1 {
@@ -206,6 +212,10 @@ impl Point for G2 {
 
         Ok(())
     }
+
+    fn negate(&mut self) {
+        self.0 = -self.0;
+    }
 }
 
 impl fmt::Display for G2 {
@@ -275,6 +285,16 @@ impl PC for PairingCurve {
     fn pair(a: &Self::G1, b: &Self::G2) -> Self::GT {
         GT(<bls377::Bls12_377 as PairingEngine>::pairing(a.0, b.0))
     }
+
+    fn multi_pair(pairs: &[(Self::G1, Self::G2)]) -> Self::GT {
+        let prepared: Vec<_> = pairs
+            .iter()
+            .map(|(a, b)| (a.0.into_affine().into(), b.0.into_affine().into()))
+            .collect();
+        GT(<bls377::Bls12_377 as PairingEngine>::product_of_pairings(
+            &prepared,
+        ))
+    }
 }
 
 // Serde implementations (ideally, these should be upstreamed to Zexe)
@@ -334,67 +354,6 @@ where
     tup.end()
 }
 
-fn deserialize_group<'de, D, C>(deserializer: D) -> Result<C, D::Error>
-where
-    D: Deserializer<'de>,
-    C: ProjectiveCurve,
-    C::Affine: CanonicalDeserialize + CanonicalSerialize,
-{
-    struct GroupVisitor<C>(PhantomData<C>);
-
-    impl<'de, C> Visitor<'de> for GroupVisitor<C>
-    where
-        C: ProjectiveCurve,
-        //C::Affine: CanonicalDeserialize + CanonicalSerialize,
-    {
-        type Value = C;
-
-        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-            formatter.write_str("a valid group element")
-        }
-
-        fn visit_seq<S>(self, mut seq: S) -> Result<C, S::Error>
-        where
-            S: SeqAccess<'de>,
-        {
-            let len = C::Affine::zero().serialized_size(); //C::Affine::SERIALIZED_SIZE;
-            let bytes: Vec<u8> = (0..len)
-                .map(|_| {
-                    seq.next_element()?
-                        .ok_or_else(|| DeserializeError::custom("could not read bytes"))
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-
-            let affine =
-                C::Affine::deserialize(&mut &bytes[..]).map_err(DeserializeError::custom)?;
-            Ok(affine.into_projective())
-        }
-    }
-
-    let visitor = GroupVisitor(PhantomData);
-    deserializer.deserialize_tuple(C::Affine::zero().serialized_size(), visitor)
-}
-
-fn serialize_group<S, C>(c: &C, s: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-    C: ProjectiveCurve,
-    C::Affine: CanonicalSerialize,
-{
-    let affine = c.into_affine();
-    let len = affine.serialized_size();
-    let mut bytes = Vec::with_capacity(len);
-    affine
-        .serialize(&mut bytes)
-        .map_err(SerializationError::custom)?;
-
-    let mut tup = s.serialize_tuple(len)?;
-    for byte in &bytes {
-        tup.serialize_element(byte)?;
-    }
-    tup.end()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -420,6 +379,20 @@ mod tests {
 
         let de: E = bincode::deserialize(&ser).unwrap();
         assert_eq!(de, sig);
+        // compressed, not at infinity
+        assert_eq!(ser[0] & 0xc0, 0x80);
+
+        let ser = bincode::serialize(&E::zero()).unwrap();
+        assert_eq!(ser[0], 0xc0);
+        assert!(ser[1..].iter().all(|b| *b == 0));
+        assert_eq!(bincode::deserialize::<E>(&ser).unwrap(), E::zero());
+
+        // an uncompressed encoding, or infinity with the sign set
+        let mut ser = ser;
+        ser[0] = 0x40;
+        assert!(bincode::deserialize::<E>(&ser).is_err());
+        ser[0] = 0xe0;
+        assert!(bincode::deserialize::<E>(&ser).is_err());
     }
 
     #[test]
@@ -438,6 +411,54 @@ mod tests {
         assert_eq!(de, sig);
     }
 
+    #[test]
+    fn scalar_vectors() {
+        let mut x = Scalar::new();
+        x.set_int(0x0102_0304_0506_0708);
+        let mut expected = vec![8, 7, 6, 5, 4, 3, 2, 1];
+        expected.resize(32, 0);
+        assert_eq!(bincode::serialize(&x).unwrap(), expected);
+
+        // r - 1 is the largest canonical encoding, r itself is rejected
+        let mut minus_one = Scalar::one();
+        minus_one.negate();
+        let raw = hex::decode("000000000080110a010000d0fe76aa5901b0375c1e4db46056a52c9a5e65ab12")
+            .unwrap();
+        assert_eq!(bincode::serialize(&minus_one).unwrap(), raw);
+        assert_eq!(bincode::deserialize::<Scalar>(&raw).unwrap(), minus_one);
+        let mut modulus = raw;
+        modulus[0] = 1;
+        assert!(bincode::deserialize::<Scalar>(&modulus).is_err());
+    }
+
+    #[test]
+    fn multi_pair() {
+        let rng = &mut rand::thread_rng();
+        let (a, b) = (G1::rand(rng), G2::rand(rng));
+        let (c, d) = (G1::rand(rng), G2::rand(rng));
+
+        let mut expected = PairingCurve::pair(&a, &b);
+        expected.add(&PairingCurve::pair(&c, &d));
+        let pairs = [(a.clone(), b.clone()), (c, d)];
+        assert_eq!(PairingCurve::multi_pair(&pairs), expected);
+        assert_eq!(PairingCurve::multi_pair(&[]), GT::one());
+
+        // e(x*a, b) * e(-a, x*b) == 1
+        let x = Scalar::rand(rng);
+        let (mut xa, mut xb, mut minus_a) = (a.clone(), b.clone(), a);
+        xa.mul(&x);
+        xb.mul(&x);
+        minus_a.negate();
+        assert!(PairingCurve::pairing_check(&[
+            (xa.clone(), b.clone()),
+            (minus_a.clone(), xb)
+        ]));
+        assert!(!PairingCurve::pairing_check(&[
+            (xa, b.clone()),
+            (minus_a, b)
+        ]));
+    }
+
     #[test]
     fn gt_exp() {
         let rng = &mut rand::thread_rng();
@@ -1,15 +1,24 @@
+use super::compressed::{deserialize_group, serialize_group};
+use crate::error::Error;
 use crate::group::PrimeOrder;
 use crate::group::{self, Element, PairingCurve as PC, Point, Scalar as Sc};
+use crate::key::{PrivateKey, PublicKey};
+use crate::min_sig;
+use crate::sig_aggregate::{self, Signature};
 use ark_bls12_381 as bls381;
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ff::PrimeField;
 use ark_ff::{Field, One, UniformRand, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+#[cfg(feature = "pairing")]
+use bls12_381::{G1Affine, G2Affine};
 use bls_crypto::{
     hash_to_curve::{try_and_increment::TryAndIncrement, HashToCurve},
     hashers::DirectHasher,
     BLSError, SIG_DOMAIN,
 };
+#[cfg(feature = "blst")]
+use blstrs::{G1Affine, G2Affine};
 use rand_core::RngCore;
 use serde::{
     de::{Error as DeserializeError, SeqAccess, Visitor},
@@ -17,10 +26,12 @@ use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
 };
 use std::{
+    convert::TryFrom,
     fmt,
     marker::PhantomData,
     ops::{AddAssign, MulAssign, Neg, SubAssign},
 };
+use zeroize::Zeroizing;
 
 use thiserror::Error;
 
@@ -34,6 +45,9 @@ pub enum ZexeError {
 
 // TODO(gakonst): Make this work with any PairingEngine.
 
+/// Serialized as 32 little-endian bytes. Unlike the points, scalars keep the arkworks encoding:
+/// it is the one of `PrivateKey::to_bytes`, and existing serialized shares keep decoding. Note
+/// that EIP-2335 keystores store secret keys big-endian instead.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Scalar(
     #[serde(deserialize_with = "deserialize_field")]
@@ -43,6 +57,7 @@ pub struct Scalar(
 
 type ZG1 = <bls381::Bls12_381 as PairingEngine>::G1Projective;
 
+/// Serialized with the ZCash compressed encoding, like `PublicKey::to_compressed`.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct G1(
     #[serde(deserialize_with = "deserialize_group")]
@@ -52,6 +67,7 @@ pub struct G1(
 
 type ZG2 = <bls381::Bls12_381 as PairingEngine>::G2Projective;
 
+/// Serialized with the ZCash compressed encoding, like `Signature::to_compressed`.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct G2(
     #[serde(deserialize_with = "deserialize_group")]
@@ -152,7 +168,14 @@ impl Element for G1 {
 impl Point for G1 {
     type Error = ZexeError;
 
+    /// Hashes with `BLS12381G1_XMD:SHA-256_SSWU_RO_` as `min_sig::hash` does.
     fn map(&mut self, data: &[u8]) -> Result<(), ZexeError> {
+        *self = Self(g1_from_affine(&G1Affine::from(min_sig::hash(data))).into_projective());
+
+        Ok(())
+    }
+
+    fn map_legacy(&mut self, data: &[u8]) -> Result<(), ZexeError> {
         let hasher = TryAndIncrement::new(&DirectHasher);
 
         let hash = hasher.hash(SIG_DOMAIN, data, &[])?;
@@ -161,6 +184,10 @@ impl Point for G1 {
 
         Ok(())
     }
+
+    fn negate(&mut self) {
+        self.0 = -self.0;
+    }
 }
 
 impl fmt::Display for G1 {
@@ -198,7 +225,14 @@ impl Element for G2 {
 impl Point for G2 {
     type Error = ZexeError;
 
+    /// Hashes with `BLS12381G2_XMD:SHA-256_SSWU_RO_` as `sig_aggregate::hash` does.
     fn map(&mut self, data: &[u8]) -> Result<(), ZexeError> {
+        *self = Self(g2_from_affine(&G2Affine::from(sig_aggregate::hash(data))).into_projective());
+
+        Ok(())
+    }
+
+    fn map_legacy(&mut self, data: &[u8]) -> Result<(), ZexeError> {
         let hasher = TryAndIncrement::new(&DirectHasher);
 
         let hash = hasher.hash(SIG_DOMAIN, data, &[])?;
@@ -206,6 +240,10 @@ impl Point for G2 {
 
         Ok(())
     }
+
+    fn negate(&mut self) {
+        self.0 = -self.0;
+    }
 }
 
 impl fmt::Display for G2 {
@@ -275,6 +313,136 @@ impl PC for PairingCurve {
     fn pair(a: &Self::G1, b: &Self::G2) -> Self::GT {
         GT(<bls381::Bls12_381 as PairingEngine>::pairing(a.0, b.0))
     }
+
+    fn multi_pair(pairs: &[(Self::G1, Self::G2)]) -> Self::GT {
+        let prepared: Vec<_> = pairs
+            .iter()
+            .map(|(a, b)| (a.0.into_affine().into(), b.0.into_affine().into()))
+            .collect();
+        GT(<bls381::Bls12_381 as PairingEngine>::product_of_pairings(
+            &prepared,
+        ))
+    }
+}
+
+// Conversions from and to the types of the `key` and `sig_aggregate` modules. Points go through
+// their uncompressed encoding, with big-endian coordinates there and little-endian ones here.
+
+const FQ_SIZE: usize = 48;
+const INFINITY_FLAG: u8 = 0x40;
+
+fn fq_to_be(f: &bls381::Fq, out: &mut [u8]) {
+    f.serialize(&mut out[..])
+        .expect("buffer has the size of an Fq");
+    out.reverse();
+}
+
+fn fq_from_be(raw: &[u8]) -> bls381::Fq {
+    let mut le = [0u8; FQ_SIZE];
+    le.copy_from_slice(raw);
+    le.reverse();
+    bls381::Fq::deserialize(&le[..]).expect("coordinates of a valid point are canonical")
+}
+
+/// Converts a point of the `key` and `sig_aggregate` backend, whose coordinates are only exposed
+/// through the uncompressed encoding.
+fn g1_from_affine(p: &G1Affine) -> bls381::G1Affine {
+    let raw = p.to_uncompressed();
+    if raw[0] & INFINITY_FLAG != 0 {
+        return bls381::G1Affine::zero();
+    }
+
+    let x = fq_from_be(&raw[..FQ_SIZE]);
+    let y = fq_from_be(&raw[FQ_SIZE..]);
+    bls381::G1Affine::new(x, y, false)
+}
+
+/// Like `g1_from_affine`, for G2.
+fn g2_from_affine(p: &G2Affine) -> bls381::G2Affine {
+    let raw = p.to_uncompressed();
+    if raw[0] & INFINITY_FLAG != 0 {
+        return bls381::G2Affine::zero();
+    }
+
+    // Fq2 elements are encoded as c1 || c0
+    let coordinate =
+        |raw: &[u8]| bls381::Fq2::new(fq_from_be(&raw[FQ_SIZE..]), fq_from_be(&raw[..FQ_SIZE]));
+    let x = coordinate(&raw[..2 * FQ_SIZE]);
+    let y = coordinate(&raw[2 * FQ_SIZE..]);
+    bls381::G2Affine::new(x, y, false)
+}
+
+impl From<&PrivateKey> for Scalar {
+    fn from(val: &PrivateKey) -> Self {
+        let bytes = Zeroizing::new(val.to_bytes());
+        Self(bls381::Fr::deserialize(&bytes[..]).expect("private keys are canonical"))
+    }
+}
+
+impl TryFrom<Scalar> for PrivateKey {
+    type Error = Error;
+
+    /// Fails for zero, which is not a valid private key.
+    fn try_from(val: Scalar) -> Result<Self, Error> {
+        let mut bytes = Zeroizing::new([0u8; 32]);
+        val.0
+            .serialize(&mut bytes[..])
+            .expect("buffer has the size of an Fr");
+
+        PrivateKey::from_byte_array(&bytes)
+    }
+}
+
+impl From<PublicKey> for G1 {
+    fn from(val: PublicKey) -> Self {
+        Self(g1_from_affine(&val.as_affine()).into_projective())
+    }
+}
+
+impl TryFrom<G1> for PublicKey {
+    type Error = Error;
+
+    /// Fails if the point is not in the prime-order subgroup.
+    fn try_from(val: G1) -> Result<Self, Error> {
+        let affine = val.0.into_affine();
+        let mut raw = [0u8; 2 * FQ_SIZE];
+        if affine.infinity {
+            raw[0] = INFINITY_FLAG;
+        } else {
+            fq_to_be(&affine.x, &mut raw[..FQ_SIZE]);
+            fq_to_be(&affine.y, &mut raw[FQ_SIZE..]);
+        }
+
+        PublicKey::from_uncompressed(&raw)
+    }
+}
+
+impl From<Signature> for G2 {
+    fn from(val: Signature) -> Self {
+        Self(g2_from_affine(&G2Affine::from(val)).into_projective())
+    }
+}
+
+impl TryFrom<G2> for Signature {
+    type Error = Error;
+
+    /// Fails if the point is not in the prime-order subgroup.
+    fn try_from(val: G2) -> Result<Self, Error> {
+        let affine = val.0.into_affine();
+        let mut raw = [0u8; 4 * FQ_SIZE];
+        if affine.infinity {
+            raw[0] = INFINITY_FLAG;
+        } else {
+            for (i, f) in [affine.x.c1, affine.x.c0, affine.y.c1, affine.y.c0]
+                .iter()
+                .enumerate()
+            {
+                fq_to_be(f, &mut raw[i * FQ_SIZE..(i + 1) * FQ_SIZE]);
+            }
+        }
+
+        Signature::from_uncompressed(&raw)
+    }
 }
 
 // Serde implementations (ideally, these should be upstreamed to Zexe)
@@ -334,70 +502,10 @@ where
     tup.end()
 }
 
-fn deserialize_group<'de, D, C>(deserializer: D) -> Result<C, D::Error>
-where
-    D: Deserializer<'de>,
-    C: ProjectiveCurve,
-    C::Affine: CanonicalDeserialize + CanonicalSerialize,
-{
-    struct GroupVisitor<C>(PhantomData<C>);
-
-    impl<'de, C> Visitor<'de> for GroupVisitor<C>
-    where
-        C: ProjectiveCurve,
-        //C::Affine: CanonicalDeserialize + CanonicalSerialize,
-    {
-        type Value = C;
-
-        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-            formatter.write_str("a valid group element")
-        }
-
-        fn visit_seq<S>(self, mut seq: S) -> Result<C, S::Error>
-        where
-            S: SeqAccess<'de>,
-        {
-            let len = C::Affine::zero().serialized_size(); //C::Affine::SERIALIZED_SIZE;
-            let bytes: Vec<u8> = (0..len)
-                .map(|_| {
-                    seq.next_element()?
-                        .ok_or_else(|| DeserializeError::custom("could not read bytes"))
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-
-            let affine =
-                C::Affine::deserialize(&mut &bytes[..]).map_err(DeserializeError::custom)?;
-            Ok(affine.into_projective())
-        }
-    }
-
-    let visitor = GroupVisitor(PhantomData);
-    deserializer.deserialize_tuple(C::Affine::zero().serialized_size(), visitor)
-}
-
-fn serialize_group<S, C>(c: &C, s: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-    C: ProjectiveCurve,
-    C::Affine: CanonicalSerialize,
-{
-    let affine = c.into_affine();
-    let len = affine.serialized_size();
-    let mut bytes = Vec::with_capacity(len);
-    affine
-        .serialize(&mut bytes)
-        .map_err(SerializationError::custom)?;
-
-    let mut tup = s.serialize_tuple(len)?;
-    for byte in &bytes {
-        tup.serialize_element(byte)?;
-    }
-    tup.end()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::key::Serialize as _;
     use serde::{de::DeserializeOwned, Serialize};
     use static_assertions::assert_impl_all;
 
@@ -420,6 +528,20 @@ mod tests {
 
         let de: E = bincode::deserialize(&ser).unwrap();
         assert_eq!(de, sig);
+        // compressed, not at infinity
+        assert_eq!(ser[0] & 0xc0, 0x80);
+
+        let ser = bincode::serialize(&E::zero()).unwrap();
+        assert_eq!(ser[0], 0xc0);
+        assert!(ser[1..].iter().all(|b| *b == 0));
+        assert_eq!(bincode::deserialize::<E>(&ser).unwrap(), E::zero());
+
+        // an uncompressed encoding, or infinity with the sign set
+        let mut ser = ser;
+        ser[0] = 0x40;
+        assert!(bincode::deserialize::<E>(&ser).is_err());
+        ser[0] = 0xe0;
+        assert!(bincode::deserialize::<E>(&ser).is_err());
     }
 
     #[test]
@@ -438,6 +560,170 @@ mod tests {
         assert_eq!(de, sig);
     }
 
+    #[test]
+    fn scalar_vectors() {
+        let mut x = Scalar::new();
+        x.set_int(0x0102_0304_0506_0708);
+        let mut expected = vec![8, 7, 6, 5, 4, 3, 2, 1];
+        expected.resize(32, 0);
+        assert_eq!(bincode::serialize(&x).unwrap(), expected);
+
+        // r - 1 is the largest canonical encoding, r itself is rejected
+        let mut minus_one = Scalar::one();
+        minus_one.negate();
+        let raw = hex::decode("00000000fffffffffe5bfeff02a4bd5305d8a10908d83933487d9d2953a7ed73")
+            .unwrap();
+        assert_eq!(bincode::serialize(&minus_one).unwrap(), raw);
+        assert_eq!(bincode::deserialize::<Scalar>(&raw).unwrap(), minus_one);
+        let mut modulus = raw;
+        modulus[0] = 1;
+        assert!(bincode::deserialize::<Scalar>(&modulus).is_err());
+
+        // the first key of tests/data.json
+        let raw = hex::decode("0ef71710671a9f1cfc4bd441c017c9b6db68491929facc68ab072a9676e9e23c")
+            .unwrap();
+        let sk = PrivateKey::from_string(
+            "27539689655622540958679105641905086851274830069992722298279813327323206776590",
+        )
+        .unwrap();
+        assert_eq!(bincode::serialize(&Scalar::from(&sk)).unwrap(), raw);
+        let mut pk = G1::one();
+        pk.mul(&bincode::deserialize(&raw).unwrap());
+        assert_eq!(
+            base64::encode(bincode::serialize(&pk).unwrap()),
+            "sr4R3I5U7nTbwHVp/XT+A7X1Ktcc1JqFebbGOHiR9aIK2YDsJ0dhjBua01hGpoo+"
+        );
+    }
+
+    #[test]
+    fn same_encodings() {
+        let rng = &mut rand::thread_rng();
+        let sk = PrivateKey::generate(rng);
+        let sig = sk.sign(b"hello");
+
+        let ser = bincode::serialize(&Scalar::from(&sk)).unwrap();
+        assert_eq!(ser, sk.as_bytes());
+        let ser = bincode::serialize(&G1::from(sk.public_key())).unwrap();
+        assert_eq!(ser, sk.public_key().as_bytes());
+        let ser = bincode::serialize(&G2::from(sig)).unwrap();
+        assert_eq!(ser, sig.as_bytes());
+    }
+
+    #[test]
+    fn test_vectors() {
+        let cases: serde_json::Value =
+            serde_json::from_slice(&std::fs::read("./tests/data.json").unwrap()).unwrap();
+
+        for case in cases["cases"].as_array().unwrap() {
+            let field = |name: &str| case[name].as_str().map(|v| base64::decode(v).unwrap());
+
+            let mut g1s = vec![field("G1Compressed").unwrap()];
+            g1s.extend(field("BLSPubKey"));
+            for raw in g1s {
+                let g1: G1 = bincode::deserialize(&raw).unwrap();
+                assert_eq!(PublicKey::try_from(g1.clone()).unwrap().as_bytes(), raw);
+                assert_eq!(bincode::serialize(&g1).unwrap(), raw);
+            }
+
+            let mut g2s = vec![field("G2Compressed").unwrap()];
+            g2s.extend(field("BLSSigG2"));
+            for raw in g2s {
+                let g2: G2 = bincode::deserialize(&raw).unwrap();
+                assert_eq!(Signature::try_from(g2.clone()).unwrap().as_bytes(), raw);
+                assert_eq!(bincode::serialize(&g2).unwrap(), raw);
+            }
+        }
+    }
+
+    #[test]
+    fn convert_keys() {
+        let rng = &mut rand::thread_rng();
+        let sk = PrivateKey::generate(rng);
+        let pk = sk.public_key();
+
+        let scalar = Scalar::from(&sk);
+        assert_eq!(PrivateKey::try_from(scalar).unwrap(), sk);
+        assert!(PrivateKey::try_from(Scalar::new()).is_err());
+
+        let mut expected = G1::one();
+        expected.mul(&scalar);
+        assert_eq!(G1::from(pk), expected);
+        assert_eq!(PublicKey::try_from(expected).unwrap(), pk);
+
+        let zero = PublicKey::try_from(G1::new()).unwrap();
+        assert_eq!(G1::from(zero), G1::new());
+    }
+
+    #[test]
+    fn convert_signatures() {
+        let rng = &mut rand::thread_rng();
+        let sk = PrivateKey::generate(rng);
+        let sig = sk.sign(b"hello");
+
+        let point = G2::from(sig);
+        assert_eq!(Signature::try_from(point.clone()).unwrap(), sig);
+        let mut hm = G2::new();
+        hm.map(b"hello").unwrap();
+        assert_eq!(
+            PairingCurve::pair(&G1::one(), &point),
+            PairingCurve::pair(&G1::from(sk.public_key()), &hm)
+        );
+
+        let point = G2::rand(rng);
+        assert_eq!(G2::from(Signature::try_from(point.clone()).unwrap()), point);
+
+        let zero = Signature::try_from(G2::new()).unwrap();
+        assert_eq!(G2::from(zero), G2::new());
+    }
+
+    #[test]
+    fn hash_to_curve() {
+        let msg = b"hello";
+
+        let mut g1 = G1::new();
+        g1.map(msg).unwrap();
+        assert_eq!(PublicKey::try_from(g1).unwrap(), min_sig::hash(msg).into());
+
+        let mut g2 = G2::new();
+        g2.map(msg).unwrap();
+        assert_eq!(
+            Signature::try_from(g2.clone()).unwrap(),
+            sig_aggregate::hash(msg).into()
+        );
+
+        let mut legacy = G2::new();
+        legacy.map_legacy(msg).unwrap();
+        assert_ne!(legacy, g2);
+    }
+
+    #[test]
+    fn multi_pair() {
+        let rng = &mut rand::thread_rng();
+        let (a, b) = (G1::rand(rng), G2::rand(rng));
+        let (c, d) = (G1::rand(rng), G2::rand(rng));
+
+        let mut expected = PairingCurve::pair(&a, &b);
+        expected.add(&PairingCurve::pair(&c, &d));
+        let pairs = [(a.clone(), b.clone()), (c, d)];
+        assert_eq!(PairingCurve::multi_pair(&pairs), expected);
+        assert_eq!(PairingCurve::multi_pair(&[]), GT::one());
+
+        // e(x*a, b) * e(-a, x*b) == 1
+        let x = Scalar::rand(rng);
+        let (mut xa, mut xb, mut minus_a) = (a.clone(), b.clone(), a);
+        xa.mul(&x);
+        xb.mul(&x);
+        minus_a.negate();
+        assert!(PairingCurve::pairing_check(&[
+            (xa.clone(), b.clone()),
+            (minus_a.clone(), xb)
+        ]));
+        assert!(!PairingCurve::pairing_check(&[
+            (xa, b.clone()),
+            (minus_a, b)
+        ]));
+    }
+
     #[test]
     fn gt_exp() {
         let rng = &mut rand::thread_rng();
@@ -0,0 +1,180 @@
+//! ZCash compressed encoding of BLS12 curve points, as used by `key` and `sig_aggregate` and by
+//! other BLS libraries: the big-endian x coordinate (c1 then c0 over Fq2), with the three most
+//! significant bits of the first byte flagging compression, the point at infinity and whether y
+//! is the lexicographically largest of y and -y.
+use ark_ec::{
+    models::SWModelParameters,
+    short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+    AffineCurve, ProjectiveCurve,
+};
+use ark_ff::{
+    fields::{Fp2, Fp2Parameters, Fp384, Fp384Parameters},
+    BigInteger, PrimeField, Zero,
+};
+use ark_serialize::CanonicalDeserialize;
+use serde::{
+    de::{Error as DeserializeError, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserializer, Serializer,
+};
+use std::{fmt, marker::PhantomData};
+
+const COMPRESSION_FLAG: u8 = 0x80;
+const INFINITY_FLAG: u8 = 0x40;
+const SIGN_FLAG: u8 = 0x20;
+const FLAGS: u8 = COMPRESSION_FLAG | INFINITY_FLAG | SIGN_FLAG;
+
+const FP_SIZE: usize = 48;
+
+/// Base field of a curve whose points can be compressed.
+pub(crate) trait CompressedField: Sized + Ord + Zero {
+    /// Size of the encoding in bytes
+    const SIZE: usize;
+
+    /// Writes the element as a big-endian integer
+    fn write_be(&self, out: &mut [u8]);
+
+    /// Reads an element written by `write_be`, rejecting non-canonical encodings
+    fn read_be(raw: &[u8]) -> Option<Self>;
+}
+
+impl<P: Fp384Parameters> CompressedField for Fp384<P> {
+    const SIZE: usize = FP_SIZE;
+
+    fn write_be(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.into_repr().to_bytes_be());
+    }
+
+    fn read_be(raw: &[u8]) -> Option<Self> {
+        let mut le = raw.to_vec();
+        le.reverse();
+        Self::deserialize(&le[..]).ok()
+    }
+}
+
+impl<P: Fp2Parameters> CompressedField for Fp2<P>
+where
+    P::Fp: CompressedField,
+{
+    const SIZE: usize = 2 * P::Fp::SIZE;
+
+    fn write_be(&self, out: &mut [u8]) {
+        let (c1, c0) = out.split_at_mut(P::Fp::SIZE);
+        self.c1.write_be(c1);
+        self.c0.write_be(c0);
+    }
+
+    fn read_be(raw: &[u8]) -> Option<Self> {
+        let (c1, c0) = raw.split_at(P::Fp::SIZE);
+        Some(Self::new(P::Fp::read_be(c0)?, P::Fp::read_be(c1)?))
+    }
+}
+
+/// Encodes the point as described in the module documentation.
+pub(crate) fn to_compressed<P>(point: &GroupAffine<P>) -> Vec<u8>
+where
+    P: SWModelParameters,
+    P::BaseField: CompressedField,
+{
+    let mut out = vec![0u8; P::BaseField::SIZE];
+    if point.infinity {
+        out[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+        return out;
+    }
+
+    point.x.write_be(&mut out);
+    out[0] |= COMPRESSION_FLAG;
+    if point.y > -point.y {
+        out[0] |= SIGN_FLAG;
+    }
+
+    out
+}
+
+/// Decodes a point produced by `to_compressed`, checking that it is on the curve and in the
+/// prime-order subgroup.
+pub(crate) fn from_compressed<P>(raw: &[u8]) -> Option<GroupAffine<P>>
+where
+    P: SWModelParameters,
+    P::BaseField: CompressedField,
+{
+    if raw.len() != P::BaseField::SIZE || raw[0] & COMPRESSION_FLAG == 0 {
+        return None;
+    }
+
+    let flags = raw[0] & FLAGS;
+    let mut x = raw.to_vec();
+    x[0] &= !FLAGS;
+
+    if flags & INFINITY_FLAG != 0 {
+        // the rest of the encoding must be zero, and the sign unset
+        return if flags & SIGN_FLAG == 0 && x.iter().all(|b| *b == 0) {
+            Some(GroupAffine::zero())
+        } else {
+            None
+        };
+    }
+
+    let x = P::BaseField::read_be(&x)?;
+    let point = GroupAffine::<P>::get_point_from_x(x, flags & SIGN_FLAG != 0)?;
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return None;
+    }
+
+    Some(point)
+}
+
+pub(crate) fn deserialize_group<'de, D, P>(deserializer: D) -> Result<GroupProjective<P>, D::Error>
+where
+    D: Deserializer<'de>,
+    P: SWModelParameters,
+    P::BaseField: CompressedField,
+{
+    struct GroupVisitor<P>(PhantomData<P>);
+
+    impl<'de, P> Visitor<'de> for GroupVisitor<P>
+    where
+        P: SWModelParameters,
+        P::BaseField: CompressedField,
+    {
+        type Value = GroupProjective<P>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a valid group element")
+        }
+
+        fn visit_seq<S>(self, mut seq: S) -> Result<GroupProjective<P>, S::Error>
+        where
+            S: SeqAccess<'de>,
+        {
+            let bytes: Vec<u8> = (0..P::BaseField::SIZE)
+                .map(|_| {
+                    seq.next_element()?
+                        .ok_or_else(|| DeserializeError::custom("could not read bytes"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let affine = from_compressed::<P>(&bytes)
+                .ok_or_else(|| DeserializeError::custom("invalid group element"))?;
+            Ok(affine.into_projective())
+        }
+    }
+
+    let visitor = GroupVisitor(PhantomData);
+    deserializer.deserialize_tuple(P::BaseField::SIZE, visitor)
+}
+
+pub(crate) fn serialize_group<S, P>(c: &GroupProjective<P>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    P: SWModelParameters,
+    P::BaseField: CompressedField,
+{
+    let bytes = to_compressed(&c.into_affine());
+
+    let mut tup = s.serialize_tuple(bytes.len())?;
+    for byte in &bytes {
+        tup.serialize_element(byte)?;
+    }
+    tup.end()
+}
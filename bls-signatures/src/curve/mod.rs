@@ -2,6 +2,8 @@
 pub mod bls12377;
 pub mod bls12381;
 
+mod compressed;
+
 use thiserror::Error;
 
 /// Error which unifies all curve specific errors from different libraries
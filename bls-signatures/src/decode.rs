@@ -0,0 +1,387 @@
+//! Bulk decoding of compressed public keys and signatures.
+//!
+//! Decompression runs in parallel under the `multicore` feature. The subgroup checks, which
+//! dominate the cost of `Serialize::from_bytes`, can be done for every point, skipped for
+//! trusted input, or batched into checks of random linear combinations of all points.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "multicore")]
+use rayon::prelude::*;
+
+#[cfg(feature = "pairing")]
+use bls12_381::{G1Affine, G2Affine};
+#[cfg(feature = "blst")]
+use blstrs::{G1Affine, G2Affine};
+use group::{prime::PrimeCurveAffine, Curve, Group, GroupEncoding};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::key::PublicKey;
+use crate::sig_aggregate::Signature;
+
+/// Security level of `SubgroupCheck::Batch`, in bits.
+const BATCH_SECURITY: u32 = 64;
+
+/// How decoded points are checked to be in the prime-order subgroup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubgroupCheck {
+    /// Checks every point on its own, like `Serialize::from_bytes`.
+    Each,
+    /// Checks random linear combinations of all points, and only checks the points on their
+    /// own if that fails. A point outside of the subgroup is missed with probability at most
+    /// 2^-64.
+    Batch,
+    /// Skips the check, only for trusted input such as a local cache.
+    Skip,
+}
+
+/// Points that can be decoded in bulk.
+trait Point: PrimeCurveAffine + GroupEncoding + Send + Sync {
+    /// Smallest prime factor of the cofactor of the curve.
+    const COFACTOR_MIN_PRIME: usize;
+
+    fn is_in_subgroup(&self) -> bool;
+}
+
+impl Point for G1Affine {
+    // h = 3 * 11^2 * 10177^2 * 859267^2 * 52437899^2
+    const COFACTOR_MIN_PRIME: usize = 3;
+
+    fn is_in_subgroup(&self) -> bool {
+        self.is_torsion_free().into()
+    }
+}
+
+impl Point for G2Affine {
+    // h = 13^2 * 23^2 * 2713 * 11953 * 262069 * p, with `p` a 448 bit prime
+    const COFACTOR_MIN_PRIME: usize = 13;
+
+    fn is_in_subgroup(&self) -> bool {
+        self.is_torsion_free().into()
+    }
+}
+
+/// Decodes compressed public keys as produced by `Serialize::as_bytes`.
+///
+/// `rng` is only used by `SubgroupCheck::Batch`. On failure, returns the indices of all
+/// entries that could not be decoded or are outside of the subgroup, in ascending order.
+pub fn public_keys_from_bytes<T, R>(
+    raw: &[T],
+    check: SubgroupCheck,
+    rng: &mut R,
+) -> Result<Vec<PublicKey>, Vec<usize>>
+where
+    T: AsRef<[u8]> + Sync,
+    R: RngCore + CryptoRng,
+{
+    let points = decode::<G1Affine, _, _>(raw, check, rng)?;
+
+    Ok(points
+        .into_iter()
+        .map(|point| PublicKey::from(point.to_curve()))
+        .collect())
+}
+
+/// Decodes compressed signatures as produced by `Serialize::as_bytes`.
+///
+/// `rng` is only used by `SubgroupCheck::Batch`. On failure, returns the indices of all
+/// entries that could not be decoded or are outside of the subgroup, in ascending order.
+pub fn signatures_from_bytes<T, R>(
+    raw: &[T],
+    check: SubgroupCheck,
+    rng: &mut R,
+) -> Result<Vec<Signature>, Vec<usize>>
+where
+    T: AsRef<[u8]> + Sync,
+    R: RngCore + CryptoRng,
+{
+    let points = decode::<G2Affine, _, _>(raw, check, rng)?;
+
+    Ok(points.into_iter().map(Signature::from).collect())
+}
+
+fn decode<P, T, R>(raw: &[T], check: SubgroupCheck, rng: &mut R) -> Result<Vec<P>, Vec<usize>>
+where
+    P: Point,
+    T: AsRef<[u8]> + Sync,
+    R: RngCore + CryptoRng,
+{
+    #[cfg(feature = "multicore")]
+    let decoded: Vec<Option<P>> = raw
+        .par_iter()
+        .map(|raw| decode_point(raw.as_ref(), check))
+        .collect();
+
+    #[cfg(not(feature = "multicore"))]
+    let decoded: Vec<Option<P>> = raw
+        .iter()
+        .map(|raw| decode_point(raw.as_ref(), check))
+        .collect();
+
+    let mut invalid: Vec<usize> = decoded
+        .iter()
+        .enumerate()
+        .filter(|(_, point)| point.is_none())
+        .map(|(i, _)| i)
+        .collect();
+
+    if check == SubgroupCheck::Batch {
+        let points: Vec<P> = decoded.iter().flatten().copied().collect();
+        if !batch_is_in_subgroup(&points, rng) {
+            invalid.extend(find_outside_subgroup(&decoded));
+            invalid.sort_unstable();
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(invalid);
+    }
+
+    Ok(decoded.into_iter().flatten().collect())
+}
+
+fn decode_point<P: Point>(raw: &[u8], check: SubgroupCheck) -> Option<P> {
+    let mut repr = P::Repr::default();
+    if raw.len() != repr.as_ref().len() {
+        return None;
+    }
+    repr.as_mut().copy_from_slice(raw);
+
+    let point: P = Option::from(P::from_bytes_unchecked(&repr))?;
+    if check == SubgroupCheck::Each && !point.is_in_subgroup() {
+        return None;
+    }
+
+    Some(point)
+}
+
+/// Returns the indices of the decoded points outside of the subgroup.
+fn find_outside_subgroup<P: Point>(decoded: &[Option<P>]) -> Vec<usize> {
+    #[cfg(feature = "multicore")]
+    let invalid = decoded
+        .par_iter()
+        .enumerate()
+        .filter(|(_, point)| matches!(point, Some(point) if !point.is_in_subgroup()))
+        .map(|(i, _)| i)
+        .collect();
+
+    #[cfg(not(feature = "multicore"))]
+    let invalid = decoded
+        .iter()
+        .enumerate()
+        .filter(|(_, point)| matches!(point, Some(point) if !point.is_in_subgroup()))
+        .map(|(i, _)| i)
+        .collect();
+
+    invalid
+}
+
+/// Checks that all points are in the subgroup by checking random linear combinations of them.
+///
+/// A point outside of the subgroup has a component of some prime order `p >= q` in the cofactor
+/// group, where `q` is the smallest prime factor of the cofactor. With coefficients below `q`,
+/// that component cancels out of the combination for at most one value of its coefficient, so
+/// every round misses it with probability at most `1/q`.
+fn batch_is_in_subgroup<P: Point, R: RngCore + CryptoRng>(points: &[P], rng: &mut R) -> bool {
+    if points.is_empty() {
+        return true;
+    }
+
+    let q = P::COFACTOR_MIN_PRIME;
+    let mut rounds = 0;
+    let mut miss_bound = 1u128;
+    while miss_bound < 1 << BATCH_SECURITY {
+        miss_bound *= q as u128;
+        rounds += 1;
+    }
+
+    // every round gets its own seed so that they can run in parallel
+    let seeds: Vec<[u8; 32]> = (0..rounds).map(|_| rng.gen()).collect();
+
+    #[cfg(feature = "multicore")]
+    let is_valid = seeds
+        .par_iter()
+        .all(|seed| combination_is_in_subgroup(points, q, seed));
+
+    #[cfg(not(feature = "multicore"))]
+    let is_valid = seeds
+        .iter()
+        .all(|seed| combination_is_in_subgroup(points, q, seed));
+
+    is_valid
+}
+
+/// Checks that `\sum_i c_i * P_i` is in the subgroup, for random `0 <= c_i < q`.
+fn combination_is_in_subgroup<P: Point>(points: &[P], q: usize, seed: &[u8; 32]) -> bool {
+    let mut rng = ChaCha8Rng::from_seed(*seed);
+
+    // `buckets[c - 1]` holds the sum of all points with coefficient `c`
+    let mut buckets = vec![P::Curve::identity(); q - 1];
+    for point in points {
+        let c = rng.gen_range(0..q);
+        if c > 0 {
+            buckets[c - 1] += point;
+        }
+    }
+
+    // \sum_c c * B_c, as a running sum from the largest coefficient down
+    let mut running = P::Curve::identity();
+    let mut combination = P::Curve::identity();
+    for bucket in buckets.iter().rev() {
+        running += bucket;
+        combination += &running;
+    }
+
+    combination.to_affine().is_in_subgroup()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ff::Field;
+    use rand::SeedableRng;
+
+    #[cfg(feature = "pairing")]
+    use bls12_381::{G1Projective, Scalar};
+    #[cfg(feature = "blst")]
+    use blstrs::{G1Projective, Scalar};
+
+    use crate::key::{PrivateKey, Serialize};
+    use crate::sig_aggregate::scalar_from_u128_bytes;
+
+    const ALL_CHECKS: [SubgroupCheck; 3] = [
+        SubgroupCheck::Each,
+        SubgroupCheck::Batch,
+        SubgroupCheck::Skip,
+    ];
+
+    fn random_g1_outside_subgroup(rng: &mut ChaCha8Rng) -> G1Affine {
+        loop {
+            let mut raw = [0u8; 48];
+            rng.fill_bytes(&mut raw);
+            raw[0] = (raw[0] & 0x1f) | 0x80;
+            let point = G1Affine::from_compressed_unchecked(&raw);
+            if point.is_some().into() {
+                let point = point.unwrap();
+                if !bool::from(point.is_torsion_free()) {
+                    return point;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bulk_roundtrip() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let private_keys: Vec<_> = (0..50).map(|_| PrivateKey::generate(&mut rng)).collect();
+        let public_keys: Vec<_> = private_keys.iter().map(|sk| sk.public_key()).collect();
+        let signatures: Vec<_> = private_keys.iter().map(|sk| sk.sign(b"hello")).collect();
+        let raw_keys: Vec<_> = public_keys.iter().map(|pk| pk.as_bytes()).collect();
+        let raw_signatures: Vec<_> = signatures.iter().map(|sig| sig.as_bytes()).collect();
+
+        for check in &ALL_CHECKS {
+            assert_eq!(
+                public_keys_from_bytes(&raw_keys, *check, &mut rng).unwrap(),
+                public_keys
+            );
+            assert_eq!(
+                signatures_from_bytes(&raw_signatures, *check, &mut rng).unwrap(),
+                signatures
+            );
+        }
+
+        let empty: &[Vec<u8>] = &[];
+        assert!(
+            public_keys_from_bytes(empty, SubgroupCheck::Batch, &mut rng)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn bulk_invalid_entries() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let mut raw_keys: Vec<_> = (0..20)
+            .map(|_| PrivateKey::generate(&mut rng).public_key().as_bytes())
+            .collect();
+        let mut raw_signatures: Vec<_> = (0..20)
+            .map(|_| PrivateKey::generate(&mut rng).sign(b"hello").as_bytes())
+            .collect();
+
+        // wrong length
+        raw_keys[3].pop();
+        raw_signatures[3].push(0);
+        // not on the curve
+        raw_keys[7] = vec![0xff; 48];
+        raw_signatures[7] = vec![0xff; 96];
+
+        for check in &ALL_CHECKS {
+            assert_eq!(
+                public_keys_from_bytes(&raw_keys, *check, &mut rng),
+                Err(vec![3, 7])
+            );
+            assert_eq!(
+                signatures_from_bytes(&raw_signatures, *check, &mut rng),
+                Err(vec![3, 7])
+            );
+        }
+
+        // outside of the subgroup
+        raw_keys[11] = random_g1_outside_subgroup(&mut rng)
+            .to_compressed()
+            .to_vec();
+        assert_eq!(
+            public_keys_from_bytes(&raw_keys, SubgroupCheck::Each, &mut rng),
+            Err(vec![3, 7, 11])
+        );
+        assert_eq!(
+            public_keys_from_bytes(&raw_keys, SubgroupCheck::Batch, &mut rng),
+            Err(vec![3, 7, 11])
+        );
+        assert_eq!(
+            public_keys_from_bytes(&raw_keys, SubgroupCheck::Skip, &mut rng),
+            Err(vec![3, 7])
+        );
+    }
+
+    #[test]
+    fn bulk_batch_small_order() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        // a point of order 3, which a single random combination misses a third of the time
+        let h_over_3 =
+            scalar_from_u128_bytes(&0x13242eaac71ca0722eaae38e55558e39u128.to_le_bytes());
+        let small_order = loop {
+            let point = G1Projective::from(random_g1_outside_subgroup(&mut rng));
+            // [r] P, computed as [r - 1] P + P
+            let cofactor_part = point * (-<Scalar as Field>::one()) + point;
+            let small_order = cofactor_part * h_over_3;
+            if !bool::from(small_order.is_identity()) {
+                break small_order;
+            }
+        };
+        assert!(bool::from(
+            (small_order + small_order + small_order).is_identity()
+        ));
+
+        let mut raw_keys: Vec<_> = (0..20)
+            .map(|_| PrivateKey::generate(&mut rng).public_key().as_bytes())
+            .collect();
+        let public_key = PublicKey::from_bytes(&raw_keys[5]).unwrap();
+        let malicious = G1Projective::from(public_key) + small_order;
+        raw_keys[5] = malicious.to_affine().to_compressed().to_vec();
+
+        for _ in 0..10 {
+            assert_eq!(
+                public_keys_from_bytes(&raw_keys, SubgroupCheck::Batch, &mut rng),
+                Err(vec![5])
+            );
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Hierarchical derivation of private keys from a single seed, following
+//! EIP-2333: https://eips.ethereum.org/EIPS/eip-2333
+//! with paths as in EIP-2334: https://eips.ethereum.org/EIPS/eip-2334
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "pairing")]
+use alloc::vec;
+#[cfg(feature = "pairing")]
+use bls12_381::{hash_to_curve::HashToField, Scalar};
+#[cfg(feature = "blst")]
+use blstrs::Scalar;
+#[cfg(feature = "pairing")]
+use ff::Field;
+#[cfg(feature = "pairing")]
+use hkdf::Hkdf;
+#[cfg(feature = "pairing")]
+use sha2::{
+    digest::generic_array::{typenum::U48, GenericArray},
+    Digest, Sha256,
+};
+#[cfg(feature = "pairing")]
+use zeroize::Zeroizing;
+
+use crate::error::Error;
+use crate::key::PrivateKey;
+
+/// Purpose of all EIP-2334 paths.
+pub const PURPOSE: u32 = 12381;
+/// Coin type of Ethereum validator keys.
+pub const ETH_COIN_TYPE: u32 = 3600;
+
+impl PrivateKey {
+    /// Derive the master key of a tree from the given seed.
+    ///
+    /// The seed must be at least 32 bytes long to be secure, will panic otherwise.
+    pub fn derive_master<T: AsRef<[u8]>>(seed: T) -> Self {
+        let seed = seed.as_ref();
+        assert!(seed.len() >= 32, "seed must be at least 32 bytes");
+
+        PrivateKey(derive_master_sk(seed))
+    }
+
+    /// Derive the child key at `index` of this key.
+    pub fn derive_child(&self, index: u32) -> Self {
+        PrivateKey(derive_child_sk(&self.0, index))
+    }
+
+    /// Derive the key at the given path, such as `m/12381/3600/0/0/0`, from the given seed.
+    ///
+    /// The seed must be at least 32 bytes long to be secure, returns `Error::InvalidSeed`
+    /// otherwise.
+    pub fn derive_path<T: AsRef<[u8]>>(seed: T, path: &str) -> Result<Self, Error> {
+        if seed.as_ref().len() < 32 {
+            return Err(Error::InvalidSeed);
+        }
+        let indices = parse_path(path)?;
+
+        Ok(indices
+            .into_iter()
+            .fold(Self::derive_master(seed), |sk, index| {
+                sk.derive_child(index)
+            }))
+    }
+}
+
+/// Path of the withdrawal key of the validator with the given index, `m/12381/3600/i/0`.
+pub fn withdrawal_path(validator: u32) -> String {
+    format!("m/{}/{}/{}/0", PURPOSE, ETH_COIN_TYPE, validator)
+}
+
+/// Path of the signing key of the validator with the given index, `m/12381/3600/i/0/0`.
+pub fn signing_path(validator: u32) -> String {
+    format!("{}/0", withdrawal_path(validator))
+}
+
+/// Parses a path of the form `m/i_1/i_2/.../i_n` into its child indices.
+pub fn parse_path(path: &str) -> Result<Vec<u32>, Error> {
+    let mut nodes = path.split('/');
+    if nodes.next() != Some("m") {
+        return Err(Error::InvalidDerivationPath);
+    }
+
+    nodes
+        .map(|node| {
+            // only plain decimal numbers, `parse` would also accept a leading `+`
+            if node.is_empty() || !node.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Error::InvalidDerivationPath);
+            }
+
+            node.parse().map_err(|_| Error::InvalidDerivationPath)
+        })
+        .collect()
+}
+
+#[cfg(feature = "pairing")]
+fn derive_master_sk(seed: &[u8]) -> Scalar {
+    hkdf_mod_r(seed)
+}
+
+#[cfg(feature = "pairing")]
+fn derive_child_sk(parent: &Scalar, index: u32) -> Scalar {
+    hkdf_mod_r(&parent_sk_to_lamport_pk(parent, index))
+}
+
+/// Calculated by `SHA256(lamport_PK)`, where `lamport_PK` holds the hash of every chunk of
+/// the lamport keys derived from the parent key and its bitwise complement.
+#[cfg(feature = "pairing")]
+fn parent_sk_to_lamport_pk(parent: &Scalar, index: u32) -> [u8; 32] {
+    let salt = index.to_be_bytes();
+
+    let mut ikm = Zeroizing::new(parent.to_bytes());
+    ikm.reverse();
+    let not_ikm = Zeroizing::new(ikm.map(|b| !b));
+
+    let mut hasher = Sha256::new();
+    for ikm in &[&ikm[..], &not_ikm[..]] {
+        let lamport_sk = ikm_to_lamport_sk(ikm, &salt);
+        for chunk in lamport_sk.chunks(32) {
+            hasher.update(Sha256::digest(chunk));
+        }
+    }
+
+    hasher.finalize().into()
+}
+
+/// Expands the key material into 255 chunks of 32 bytes.
+#[cfg(feature = "pairing")]
+fn ikm_to_lamport_sk(ikm: &[u8], salt: &[u8]) -> Zeroizing<Vec<u8>> {
+    let prk = Hkdf::<Sha256>::new(Some(salt), ikm);
+
+    let mut okm = Zeroizing::new(vec![0u8; 32 * 255]);
+    assert!(prk.expand(&[], &mut okm).is_ok());
+
+    okm
+}
+
+/// Generates a secret key as defined in
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.3
+/// which, unlike `key::key_gen`, hashes the salt until the key is not zero.
+#[cfg(feature = "pairing")]
+fn hkdf_mod_r(ikm: &[u8]) -> Scalar {
+    let mut salt = Sha256::digest(b"BLS-SIG-KEYGEN-SALT-");
+
+    let mut msg = Zeroizing::new(ikm.to_vec());
+    // append zero byte
+    msg.push(0);
+
+    loop {
+        let prk = Hkdf::<Sha256>::new(Some(&salt), &msg);
+
+        let mut result = Zeroizing::new([0u8; 48]);
+        assert!(prk.expand(&[0, 48], &mut result[..]).is_ok());
+
+        let sk = Scalar::from_okm(GenericArray::<u8, U48>::from_slice(&result[..]));
+        if !bool::from(sk.is_zero()) {
+            return sk;
+        }
+
+        salt = Sha256::digest(&salt);
+    }
+}
+
+#[cfg(feature = "blst")]
+fn derive_master_sk(seed: &[u8]) -> Scalar {
+    use core::convert::TryInto;
+
+    let mut out = blst_lib::blst_scalar::default();
+    unsafe { blst_lib::blst_derive_master_eip2333(&mut out, seed.as_ptr(), seed.len()) };
+
+    out.try_into().expect("invalid key derived")
+}
+
+#[cfg(feature = "blst")]
+fn derive_child_sk(parent: &Scalar, index: u32) -> Scalar {
+    use core::convert::TryInto;
+
+    let mut out = blst_lib::blst_scalar::default();
+    unsafe { blst_lib::blst_derive_child_eip2333(&mut out, &(*parent).into(), index) };
+
+    out.try_into().expect("invalid key derived")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::convert::TryFrom;
+
+    use ff::PrimeField;
+
+    struct TestVector {
+        seed: &'static str,
+        master_sk: &'static str,
+        child_index: u32,
+        child_sk: &'static str,
+    }
+
+    // https://eips.ethereum.org/EIPS/eip-2333#test-cases
+    const TEST_VECTORS: &[TestVector] = &[
+        TestVector {
+            seed: "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            master_sk: "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+            child_index: 0,
+            child_sk: "20397789859736650942317412262472558107875392172444076792671091975210932703118",
+        },
+        TestVector {
+            seed: "3141592653589793238462643383279502884197169399375105820974944592",
+            master_sk: "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+            child_index: 3141592653,
+            child_sk: "25457201688850691947727629385191704516744796114925897962676248250929345014287",
+        },
+        TestVector {
+            seed: "0099FF991111002299DD7744EE3355BBDD8844115566CC55663355668888CC00",
+            master_sk: "27580842291869792442942448775674722299803720648445448686099262467207037398656",
+            child_index: 4294967295,
+            child_sk: "29358610794459428860402234341874281240803786294062035874021252734817515685787",
+        },
+        TestVector {
+            seed: "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+            master_sk: "19022158461524446591288038168518313374041767046816487870552872741050760015818",
+            child_index: 42,
+            child_sk: "31372231650479070279774297061823572166496564838472787488249775572789064611981",
+        },
+    ];
+
+    fn decode_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_vectors() {
+        for vector in TEST_VECTORS {
+            let seed = decode_hex(vector.seed);
+            let master = PrivateKey::derive_master(&seed);
+            let child = master.derive_child(vector.child_index);
+
+            assert_eq!(
+                master,
+                PrivateKey::try_from(Scalar::from_str_vartime(vector.master_sk).unwrap()).unwrap()
+            );
+            assert_eq!(
+                child,
+                PrivateKey::try_from(Scalar::from_str_vartime(vector.child_sk).unwrap()).unwrap()
+            );
+
+            let path = format!("m/{}", vector.child_index);
+            assert_eq!(PrivateKey::derive_path(&seed, &path).unwrap(), child);
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(parse_path("m").unwrap(), Vec::<u32>::new());
+        assert_eq!(
+            parse_path("m/12381/3600/0/0/0").unwrap(),
+            vec![12381, 3600, 0, 0, 0]
+        );
+        assert_eq!(
+            parse_path(&signing_path(7)).unwrap(),
+            vec![12381, 3600, 7, 0, 0]
+        );
+        assert_eq!(
+            parse_path(&withdrawal_path(7)).unwrap(),
+            vec![12381, 3600, 7, 0]
+        );
+
+        for path in &[
+            "",
+            "/12381",
+            "n/12381",
+            "m/",
+            "m//0",
+            "m/12381/",
+            "m/+1",
+            "m/-1",
+            "m/0x10",
+            "m/4294967296",
+        ] {
+            assert!(parse_path(path).is_err(), "{}", path);
+        }
+
+        let seed = decode_hex(TEST_VECTORS[0].seed);
+        let signing = PrivateKey::derive_path(&seed, &signing_path(0)).unwrap();
+        let withdrawal = PrivateKey::derive_path(&seed, &withdrawal_path(0)).unwrap();
+        assert_eq!(withdrawal.derive_child(0), signing);
+        assert_ne!(withdrawal, signing);
+        assert!(matches!(
+            PrivateKey::derive_path(&seed[..31], &signing_path(0)),
+            Err(Error::InvalidSeed)
+        ));
+    }
+}
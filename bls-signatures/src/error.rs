@@ -1,19 +1,124 @@
-use thiserror::Error;
+use core::fmt;
 
-#[derive(Debug, Error)]
+#[derive(Debug)]
 pub enum Error {
-    #[error("Size mismatch")]
     SizeMismatch,
-    #[error("Io error: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("Group decode error")]
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
     GroupDecode,
-    #[error("Curve decode error")]
     CurveDecode,
-    #[error("Prime field decode error")]
     FieldDecode,
-    #[error("Invalid Private Key")]
     InvalidPrivateKey,
-    #[error("Zero sized input")]
+    InvalidPublicKey,
+    InvalidSignature,
     ZeroSizedInput,
+    InvalidDst,
+    InvalidDerivationPath,
+    InvalidSeed,
+    InvalidKeystore,
+    InvalidPassword,
+    #[cfg(feature = "keystore")]
+    Json(serde_json::Error),
+    Verification(VerificationError),
 }
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::SizeMismatch => write!(f, "Size mismatch"),
+            #[cfg(feature = "std")]
+            Error::Io(err) => write!(f, "Io error: {}", err),
+            Error::GroupDecode => write!(f, "Group decode error"),
+            Error::CurveDecode => write!(f, "Curve decode error"),
+            Error::FieldDecode => write!(f, "Prime field decode error"),
+            Error::InvalidPrivateKey => write!(f, "Invalid Private Key"),
+            Error::InvalidPublicKey => write!(f, "Invalid Public Key"),
+            Error::InvalidSignature => write!(f, "Invalid Signature"),
+            Error::ZeroSizedInput => write!(f, "Zero sized input"),
+            Error::InvalidDst => write!(f, "Invalid domain separation tag"),
+            Error::InvalidDerivationPath => write!(f, "Invalid derivation path"),
+            Error::InvalidSeed => write!(f, "Seed must be at least 32 bytes"),
+            Error::InvalidKeystore => write!(f, "Invalid keystore"),
+            Error::InvalidPassword => write!(f, "Invalid password"),
+            #[cfg(feature = "keystore")]
+            Error::Json(err) => write!(f, "Json error: {}", err),
+            Error::Verification(err) => write!(f, "Verification failed: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            #[cfg(feature = "keystore")]
+            Error::Json(err) => Some(err),
+            Error::Verification(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for signature::Error {
+    #[cfg(not(feature = "std"))]
+    fn from(_err: Error) -> signature::Error {
+        signature::Error::new()
+    }
+
+    #[cfg(feature = "std")]
+    fn from(err: Error) -> signature::Error {
+        signature::Error::from_source(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(feature = "keystore")]
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl From<VerificationError> for Error {
+    fn from(err: VerificationError) -> Self {
+        Error::Verification(err)
+    }
+}
+
+/// Reasons for a signature to be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationError {
+    Empty,
+    LengthMismatch(usize, usize),
+    DuplicateMessage(usize, usize),
+    IdentityPublicKey(usize),
+    InvalidSignature,
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::Empty => write!(f, "no messages or public keys"),
+            VerificationError::LengthMismatch(messages, public_keys) => {
+                write!(f, "{} messages but {} public keys", messages, public_keys)
+            }
+            VerificationError::DuplicateMessage(i, j) => {
+                write!(f, "messages {} and {} are equal", i, j)
+            }
+            VerificationError::IdentityPublicKey(i) => {
+                write!(f, "public key {} is the identity", i)
+            }
+            VerificationError::InvalidSignature => write!(f, "pairing check failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerificationError {}
@@ -60,6 +60,47 @@ pub trait Point: Element {
 
     /// Maps the provided data to a group element
     fn map(&mut self, data: &[u8]) -> Result<(), <Self as Point>::Error>;
+
+    /// Negates the element in place
+    fn negate(&mut self);
+
+    /// Maps the provided data to a group element with the try-and-increment method, for
+    /// curves whose `map` implements another one. Only kept for compatibility.
+    fn map_legacy(&mut self, data: &[u8]) -> Result<(), <Self as Point>::Error> {
+        self.map(data)
+    }
+}
+
+/// Selects how a signature scheme hashes messages to points.
+pub trait HashMode: Clone + Debug + Send + Sync {
+    /// Maps the provided data to a group element
+    fn map<P: Point>(point: &mut P, data: &[u8]) -> Result<(), <P as Point>::Error>;
+}
+
+/// Hashes with `Point::map`, which is RFC 9380 hash-to-curve on BLS12-381.
+///
+/// This is a breaking change for BLS12-381: messages are hashed with the
+/// `BLS_SIG_BLS12381G{1,2}_XMD:SHA-256_SSWU_RO_NUL_` ciphersuites, so signatures made by
+/// earlier versions of `schemes::bls12_381::{G1Scheme, G2Scheme}` no longer verify. Use
+/// `schemes::bls12_381::{LegacyG1Scheme, LegacyG2Scheme}` to check them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Standard;
+
+impl HashMode for Standard {
+    fn map<P: Point>(point: &mut P, data: &[u8]) -> Result<(), <P as Point>::Error> {
+        point.map(data)
+    }
+}
+
+/// Hashes with `Point::map_legacy`, to check signatures made before BLS12-381 switched to
+/// RFC 9380 hash-to-curve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Legacy;
+
+impl HashMode for Legacy {
+    fn map<P: Point>(point: &mut P, data: &[u8]) -> Result<(), <P as Point>::Error> {
+        point.map_legacy(data)
+    }
 }
 
 /// A group holds functionalities to create scalar and points related; it is
@@ -94,6 +135,15 @@ pub trait PairingCurve: Debug {
 
     /// Perfors a pairing operation between the 2 group elements
     fn pair(a: &Self::G1, b: &Self::G2) -> Self::GT;
+
+    /// Computes the product of the pairings of all pairs, with one Miller loop per
+    /// pair but a single final exponentiation
+    fn multi_pair(pairs: &[(Self::G1, Self::G2)]) -> Self::GT;
+
+    /// Checks that the product of the pairings of all pairs is one
+    fn pairing_check(pairs: &[(Self::G1, Self::G2)]) -> bool {
+        Self::multi_pair(pairs) == Self::GT::one()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
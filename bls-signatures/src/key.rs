@@ -1,19 +1,35 @@
+use alloc::vec::Vec;
+use core::convert::{TryFrom, TryInto};
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+#[cfg(feature = "std")]
 use std::io;
 
-use ff::{PrimeField, PrimeFieldBits};
+use ff::{Field, PrimeField, PrimeFieldBits};
 use group::Curve;
 use rand_core::{CryptoRng, RngCore};
+use serde::{
+    de::{Error as DeserializeError, SeqAccess, Visitor},
+    Deserializer, Serializer,
+};
+use signature::{Keypair, Signer, Verifier};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[cfg(feature = "pairing")]
-use bls12_381::{hash_to_curve::HashToField, G1Affine, G1Projective, Scalar};
+use bls12_381::{
+    hash_to_curve::HashToField, G1Affine, G1Projective, G2Affine, G2Projective, Scalar,
+};
 #[cfg(feature = "pairing")]
 use hkdf::Hkdf;
 #[cfg(feature = "pairing")]
-use sha2::{digest::generic_array::typenum::U48, Sha256};
-#[cfg(feature = "pairing")]
-use chacha20poly1305::aead::generic_array::GenericArray;
-//#[cfg(feature = "blst")]
-//use blstrs::{G1Affine, G1Projective, G2Affine, Scalar};
+use sha2::{
+    digest::generic_array::{typenum::U48, GenericArray},
+    Sha256,
+};
+#[cfg(feature = "blst")]
+use blstrs::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
 #[cfg(feature = "blst")]
 use group::prime::PrimeCurveAffine;
 
@@ -22,12 +38,18 @@ pub(crate) struct ScalarRepr(<Scalar as PrimeFieldBits>::ReprBits);
 use crate::error::Error;
 use crate::sig_aggregate::*;
 
+pub(crate) const FR_SIZE: usize = (Scalar::NUM_BITS as usize).div_ceil(8);
 pub(crate) const G1_COMPRESSED_SIZE: usize = 48;
+pub(crate) const G1_UNCOMPRESSED_SIZE: usize = 96;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct PublicKey(pub(crate) G1Projective);
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// A BLS private key.
+///
+/// The scalar is wiped from memory when the key is dropped, and is neither printed by `Debug`
+/// nor compared in variable time.
+#[derive(Clone)]
 pub struct PrivateKey(pub(crate) Scalar);
 
 impl From<G1Projective> for PublicKey {
@@ -41,9 +63,15 @@ impl From<PublicKey> for G1Projective {
     }
 }
 
-impl From<Scalar> for PrivateKey {
-    fn from(val: Scalar) -> Self {
-        PrivateKey(val)
+impl TryFrom<Scalar> for PrivateKey {
+    type Error = Error;
+
+    fn try_from(val: Scalar) -> Result<Self, Error> {
+        if bool::from(val.is_zero()) {
+            return Err(Error::InvalidPrivateKey);
+        }
+
+        Ok(PrivateKey(val))
     }
 }
 
@@ -55,28 +83,77 @@ impl From<PrivateKey> for Scalar {
 
 impl From<PrivateKey> for ScalarRepr {
     fn from(val: PrivateKey) -> Self {
-        ScalarRepr(val.0.to_le_bits().into_inner())
+        (&val).into()
     }
 }
 
 impl<'a> From<&'a PrivateKey> for ScalarRepr {
     fn from(val: &'a PrivateKey) -> Self {
-        (*val).into()
+        ScalarRepr(val.0.to_le_bits().into_inner())
+    }
+}
+
+impl Drop for ScalarRepr {
+    fn drop(&mut self) {
+        self.0.zeroize();
     }
 }
 
-pub trait Serialize: ::std::fmt::Debug + Sized {
+impl ZeroizeOnDrop for ScalarRepr {}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PrivateKey(..)")
+    }
+}
+
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for PrivateKey {}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        zeroize_scalar(&mut self.0);
+    }
+}
+
+impl ZeroizeOnDrop for PrivateKey {}
+
+#[cfg(feature = "pairing")]
+fn zeroize_scalar(s: &mut Scalar) {
+    s.zeroize();
+}
+
+/// `blstrs` has no `Zeroize` implementation, so do what `zeroize` does for `Copy` types.
+#[cfg(feature = "blst")]
+fn zeroize_scalar(s: &mut Scalar) {
+    use core::sync::atomic::{compiler_fence, Ordering};
+
+    unsafe { core::ptr::write_volatile(s, Scalar::zero()) };
+    compiler_fence(Ordering::SeqCst);
+}
+
+pub trait Serialize: fmt::Debug + Sized {
     /// Writes the key to the given writer.
-    fn write_bytes(&self, dest: &mut impl io::Write) -> io::Result<()>;
+    #[cfg(feature = "std")]
+    fn write_bytes(&self, dest: &mut impl io::Write) -> io::Result<()> {
+        dest.write_all(&self.as_bytes())
+    }
 
-    /// Recreate the key from bytes in the same form as `write_bytes` produced.
+    /// Recreate the key from bytes in the same form as `as_bytes` produced.
     fn from_bytes(raw: &[u8]) -> Result<Self, Error>;
 
-    fn as_bytes(&self) -> Vec<u8> {
-        let mut res = Vec::with_capacity(8 * 4);
-        self.write_bytes(&mut res).expect("preallocated");
-        res
-    }
+    fn as_bytes(&self) -> Vec<u8>;
 }
 
 impl PrivateKey {
@@ -91,40 +168,72 @@ impl PrivateKey {
     pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
         // IKM must be at least 32 bytes long:
         // https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-00#section-2.3
-        let mut ikm = [0u8; 32];
-        rng.try_fill_bytes(&mut ikm)
+        let mut ikm = Zeroizing::new([0u8; 32]);
+        rng.try_fill_bytes(&mut ikm[..])
             .expect("unable to produce secure randomness");
 
-        Self::new(ikm)
+        Self::new(&ikm[..])
     }
 
     /// Sign the given message.
     /// Calculated by `signature = hash_into_g2(message) * sk`
-    #[cfg(feature = "pairing")]
     pub fn sign<T: AsRef<[u8]>>(&self, message: T) -> Signature {
-        let mut p = hash(message.as_ref());
+        self.sign_hash(hash(message.as_ref())).into()
+    }
+
+    /// Sign the given message under the given domain separation tag.
+    /// Calculated by `signature = hash_dst_into_g2(message) * sk`
+    pub fn sign_with_dst<T: AsRef<[u8]>>(
+        &self,
+        message: T,
+        dst: &[u8],
+    ) -> Result<Signature, Error> {
+        let p = hash_with_dst(message.as_ref(), dst)?;
+
+        Ok(self.sign_hash(p).into())
+    }
+
+    /// Sign the given message, augmented with the public key as in the IRTF's "AUG" scheme.
+    /// Calculated by `signature = hash_aug_into_g2(pk || message) * sk`
+    pub fn sign_augmented<T: AsRef<[u8]>>(&self, message: T) -> Signature {
+        self.sign_hash(hash_augmented(&self.public_key(), message.as_ref()))
+            .into()
+    }
+
+    /// Sign the given message under the IRTF's "POP" scheme, so that signatures of the same
+    /// message can be checked with `fast_aggregate_verify`.
+    /// Calculated by `signature = hash_pop_scheme_into_g2(message) * sk`
+    pub fn sign_pop<T: AsRef<[u8]>>(&self, message: T) -> Signature {
+        self.sign_hash(hash_pop_scheme(message.as_ref())).into()
+    }
+
+    /// Prove possession of this private key.
+    /// Calculated by `proof = hash_pop_into_g2(pk) * sk`
+    pub fn pop_prove(&self) -> ProofOfPossession {
+        self.sign_hash(pop_hash(&self.public_key())).into()
+    }
+
+    #[cfg(feature = "pairing")]
+    fn sign_hash(&self, mut p: G2Projective) -> G2Affine {
         p *= self.0;
 
         p.into()
     }
 
-    /// Sign the given message.
-    /// Calculated by `signature = hash_into_g2(message) * sk`
     #[cfg(feature = "blst")]
-    pub fn sign<T: AsRef<[u8]>>(&self, message: T) -> Signature {
-        let p = hash(message.as_ref());
+    fn sign_hash(&self, p: G2Projective) -> G2Affine {
         let mut sig = G2Affine::identity();
 
         unsafe {
             blst_lib::blst_sign_pk2_in_g1(
-                std::ptr::null_mut(),
+                core::ptr::null_mut(),
                 sig.as_mut(),
                 p.as_ref(),
                 &self.0.into(),
             );
         }
 
-        sig.into()
+        sig
     }
 
     /// Get the public key for this private key.
@@ -144,77 +253,318 @@ impl PrivateKey {
         let mut pk = G1Affine::identity();
 
         unsafe {
-            blst_lib::blst_sk_to_pk2_in_g1(std::ptr::null_mut(), pk.as_mut(), &self.0.into());
+            blst_lib::blst_sk_to_pk2_in_g1(core::ptr::null_mut(), pk.as_mut(), &self.0.into());
         }
 
         PublicKey(pk.into())
     }
 
+    /// Encodes the key as a little-endian integer. The caller is responsible for zeroizing the
+    /// result, e.g. by wrapping it in `Zeroizing`.
+    pub fn to_bytes(&self) -> [u8; FR_SIZE] {
+        let repr = ScalarRepr::from(self);
+        let mut res = [0u8; FR_SIZE];
+        let digits = repr.0.iter().flat_map(|digit| digit.to_le_bytes());
+        for (byte, digit_byte) in res.iter_mut().zip(digits) {
+            *byte = digit_byte;
+        }
+
+        res
+    }
+
+    /// Decodes a key produced by `to_bytes`, rejecting zero and non-canonical encodings.
+    pub fn from_byte_array(raw: &[u8; FR_SIZE]) -> Result<Self, Error> {
+        Scalar::from_repr_vartime(*raw)
+            .ok_or(Error::InvalidPrivateKey)
+            .and_then(PrivateKey::try_from)
+    }
+
     /// Deserializes a private key from the field element as a decimal number.
     pub fn from_string<T: AsRef<str>>(s: T) -> Result<Self, Error> {
         match Scalar::from_str_vartime(s.as_ref()) {
-            Some(f) => Ok(f.into()),
+            Some(f) => PrivateKey::try_from(f),
             None => Err(Error::InvalidPrivateKey),
         }
     }
 }
 
 impl Serialize for PrivateKey {
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        let raw: &[u8; FR_SIZE] = raw.try_into().map_err(|_| Error::SizeMismatch)?;
+
+        Self::from_byte_array(raw)
+    }
+
+    #[cfg(feature = "std")]
     fn write_bytes(&self, dest: &mut impl io::Write) -> io::Result<()> {
-        for digit in &self.0.to_le_bits().data {
-            dest.write_all(&digit.to_le_bytes())?;
+        dest.write_all(&Zeroizing::new(self.to_bytes())[..])
+    }
+
+    /// The returned bytes are not zeroized on drop, prefer `to_bytes` wrapped in `Zeroizing`.
+    fn as_bytes(&self) -> Vec<u8> {
+        Zeroizing::new(self.to_bytes()).to_vec()
+    }
+}
+
+impl PublicKey {
+    pub fn as_affine(&self) -> G1Affine {
+        self.0.to_affine()
+    }
+
+    /// Encodes the key with point compression, as `as_bytes` does.
+    pub fn to_compressed(&self) -> [u8; G1_COMPRESSED_SIZE] {
+        self.as_affine().to_compressed()
+    }
+
+    /// Decodes a key produced by `to_compressed`, checking that it is on the curve and in the
+    /// prime-order subgroup.
+    pub fn from_compressed(raw: &[u8; G1_COMPRESSED_SIZE]) -> Result<Self, Error> {
+        let affine: G1Affine =
+            Option::from(G1Affine::from_compressed(raw)).ok_or(Error::GroupDecode)?;
+
+        Ok(PublicKey(affine.into()))
+    }
+
+    /// Encodes the key without point compression, which is faster to decode.
+    pub fn to_uncompressed(&self) -> [u8; G1_UNCOMPRESSED_SIZE] {
+        self.as_affine().to_uncompressed()
+    }
+
+    /// Decodes a key produced by `to_uncompressed`, checking that it is on the curve and in
+    /// the prime-order subgroup.
+    pub fn from_uncompressed(raw: &[u8; G1_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        let affine: G1Affine =
+            Option::from(G1Affine::from_uncompressed(raw)).ok_or(Error::GroupDecode)?;
+
+        Ok(PublicKey(affine.into()))
+    }
+
+    /// Decodes a key produced by `to_uncompressed` without any checks, only for trusted
+    /// sources such as a local cache.
+    pub fn from_uncompressed_unchecked(raw: &[u8; G1_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        let affine: G1Affine =
+            Option::from(G1Affine::from_uncompressed_unchecked(raw)).ok_or(Error::GroupDecode)?;
+
+        Ok(PublicKey(affine.into()))
+    }
+
+    /// Runs KeyValidate, rejecting the identity and points outside of the prime-order subgroup.
+    /// See Section 2.5 of the IRTF's BLS signatures spec:
+    /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.5
+    pub fn validate(&self) -> Result<ValidatedPublicKey, Error> {
+        let affine = self.as_affine();
+        if bool::from(affine.is_identity()) || !bool::from(affine.is_torsion_free()) {
+            return Err(Error::InvalidPublicKey);
         }
 
-        Ok(())
+        Ok(ValidatedPublicKey(*self))
     }
 
-    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
-        const FR_SIZE: usize = (Scalar::NUM_BITS as usize + 8 - 1) / 8;
-        if raw.len() != FR_SIZE {
-            return Err(Error::SizeMismatch);
+    /// Verifies the signature, after validating both the key and the signature.
+    pub fn verify<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> bool {
+        self.try_verify(sig, message).is_ok()
+    }
+
+    /// Like `verify`, but reports why verification failed.
+    pub fn try_verify<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> Result<(), Error> {
+        let public_key = self.validate()?;
+        let sig = sig.validate()?;
+
+        try_verify_messages(&sig, &[message.as_ref()], &[public_key])
+    }
+
+    /// Verifies a signature produced by `PrivateKey::sign_with_dst` under the same tag.
+    pub fn verify_with_dst<T: AsRef<[u8]>>(&self, sig: Signature, message: T, dst: &[u8]) -> bool {
+        match (self.validate(), sig.validate()) {
+            (Ok(public_key), Ok(sig)) => {
+                verify_messages_with_dst(&sig, &[message.as_ref()], &[public_key], dst)
+            }
+            _ => false,
         }
+    }
 
-        let mut res = [0u8; FR_SIZE];
-        res.copy_from_slice(&raw[..FR_SIZE]);
+    /// Verifies a signature produced by `PrivateKey::sign_augmented`.
+    pub fn verify_augmented<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> bool {
+        match (self.validate(), sig.validate()) {
+            (Ok(public_key), Ok(sig)) => {
+                aggregate_verify_augmented(&sig, &[message.as_ref()], &[public_key])
+            }
+            _ => false,
+        }
+    }
 
-        // TODO: once zero keys are rejected, insert check for zero.
+    /// Verifies a signature produced by `PrivateKey::sign_pop`.
+    pub fn verify_pop_scheme<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> bool {
+        match (self.validate(), sig.validate()) {
+            (Ok(public_key), Ok(sig)) => {
+                verify(&sig, &[hash_pop_scheme(message.as_ref())], &[public_key])
+            }
+            _ => false,
+        }
+    }
 
-        Scalar::from_repr_vartime(res)
-            .map(Into::into)
-            .ok_or(Error::InvalidPrivateKey)
+    /// Verifies that the holder of this public key also holds the private key.
+    pub fn pop_verify(&self, proof: ProofOfPossession) -> bool {
+        verify_pop(&proof, self)
     }
 }
 
-impl PublicKey {
-    pub fn as_affine(&self) -> G1Affine {
-        self.0.to_affine()
+impl Signer<Signature> for PrivateKey {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(PrivateKey::sign(self, msg))
     }
+}
 
-    pub fn verify<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> bool {
-        verify_messages(&sig, &[message.as_ref()], &[*self])
+impl Keypair for PrivateKey {
+    type VerifyingKey = PublicKey;
+
+    fn verifying_key(&self) -> PublicKey {
+        self.public_key()
+    }
+}
+
+impl Verifier<Signature> for PublicKey {
+    fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), signature::Error> {
+        Ok(self.try_verify(*signature, msg)?)
     }
 }
 
 impl Serialize for PublicKey {
-    fn write_bytes(&self, dest: &mut impl io::Write) -> io::Result<()> {
-        let t = self.0.to_affine();
-        let tmp = t.to_compressed();
-        dest.write_all(tmp.as_ref())?;
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        let raw: &[u8; G1_COMPRESSED_SIZE] = raw.try_into().map_err(|_| Error::SizeMismatch)?;
+
+        Self::from_compressed(raw)
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.to_compressed().to_vec()
+    }
+}
+
+/// A public key that passed KeyValidate, as required by all verification functions.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ValidatedPublicKey(PublicKey);
+
+impl ValidatedPublicKey {
+    /// Skips KeyValidate, only for keys from a trusted source that were validated before.
+    pub fn new_unchecked(public_key: PublicKey) -> Self {
+        ValidatedPublicKey(public_key)
+    }
+}
 
-        Ok(())
+impl Deref for ValidatedPublicKey {
+    type Target = PublicKey;
+
+    fn deref(&self) -> &PublicKey {
+        &self.0
     }
+}
 
+impl From<ValidatedPublicKey> for PublicKey {
+    fn from(val: ValidatedPublicKey) -> Self {
+        val.0
+    }
+}
+
+impl TryFrom<PublicKey> for ValidatedPublicKey {
+    type Error = Error;
+
+    fn try_from(val: PublicKey) -> Result<Self, Error> {
+        val.validate()
+    }
+}
+
+impl Serialize for ValidatedPublicKey {
     fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
-        if raw.len() != G1_COMPRESSED_SIZE {
-            return Err(Error::SizeMismatch);
+        PublicKey::from_bytes(raw)?.validate()
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes()
+    }
+}
+
+// Serde implementations, using hex strings for human readable formats and the bytes of
+// `Serialize::as_bytes` otherwise.
+
+macro_rules! impl_serde {
+    ($type:ty) => {
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                $crate::key::serialize_bytes(self, s)
+            }
         }
 
-        let mut res = [0u8; G1_COMPRESSED_SIZE];
-        res.as_mut().copy_from_slice(raw);
-        let affine: G1Affine =
-            Option::from(G1Affine::from_compressed(&res)).ok_or(Error::GroupDecode)?;
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                $crate::key::deserialize_bytes(d)
+            }
+        }
+    };
+}
 
-        Ok(PublicKey(affine.into()))
+pub(crate) use impl_serde;
+
+impl_serde!(PrivateKey);
+impl_serde!(PublicKey);
+impl_serde!(ValidatedPublicKey);
+
+pub(crate) fn serialize_bytes<S, T>(t: &T, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let bytes = t.as_bytes();
+    if s.is_human_readable() {
+        s.serialize_str(&hex::encode(bytes))
+    } else {
+        s.serialize_bytes(&bytes)
+    }
+}
+
+pub(crate) fn deserialize_bytes<'de, D, T>(d: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Serialize,
+{
+    struct BytesVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for BytesVisitor<T>
+    where
+        T: Serialize,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a hex string or bytes")
+        }
+
+        fn visit_str<E: DeserializeError>(self, v: &str) -> Result<T, E> {
+            let bytes = hex::decode(v).map_err(E::custom)?;
+            T::from_bytes(&bytes).map_err(E::custom)
+        }
+
+        fn visit_bytes<E: DeserializeError>(self, v: &[u8]) -> Result<T, E> {
+            T::from_bytes(v).map_err(E::custom)
+        }
+
+        fn visit_seq<S>(self, mut seq: S) -> Result<T, S::Error>
+        where
+            S: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            T::from_bytes(&bytes).map_err(DeserializeError::custom)
+        }
+    }
+
+    let visitor = BytesVisitor(PhantomData);
+    if d.is_human_readable() {
+        d.deserialize_str(visitor)
+    } else {
+        d.deserialize_bytes(visitor)
     }
 }
 
@@ -229,25 +579,23 @@ fn key_gen<T: AsRef<[u8]>>(data: T) -> Scalar {
     assert!(data.len() >= 32, "IKM must be at least 32 bytes");
 
     // HKDF-Extract
-    let mut msg = data.as_ref().to_vec();
+    let mut msg = Zeroizing::new(data.as_ref().to_vec());
     // append zero byte
     msg.push(0);
     let prk = Hkdf::<Sha256>::new(Some(SALT), &msg);
 
     // HKDF-Expand
     // `result` has enough length to hold the output from HKDF expansion
-    let mut result = GenericArray::<u8, U48>::default();
-    assert!(prk.expand(&[0, 48], &mut result).is_ok());
+    let mut result = Zeroizing::new([0u8; 48]);
+    assert!(prk.expand(&[0, 48], &mut result[..]).is_ok());
 
-    Scalar::from_okm(&result)
+    Scalar::from_okm(GenericArray::<u8, U48>::from_slice(&result[..]))
 }
 
 /// Generates a secret key as defined in
 /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-2.3
 #[cfg(feature = "blst")]
 fn key_gen<T: AsRef<[u8]>>(data: T) -> Scalar {
-    use std::convert::TryInto;
-
     let data = data.as_ref();
     assert!(data.len() >= 32, "IKM must be at least 32 bytes");
 
@@ -273,6 +621,39 @@ mod tests {
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
 
+    fn sign_and_verify<K>(key: &K, msg: &[u8]) -> Result<(), signature::Error>
+    where
+        K: Signer<Signature> + Keypair,
+        K::VerifyingKey: Verifier<Signature>,
+    {
+        let sig = key.try_sign(msg)?;
+        key.verifying_key().verify(msg, &sig)
+    }
+
+    #[test]
+    fn test_signature_traits() {
+        use signature::SignatureEncoding;
+
+        let rng = &mut ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(rng);
+        let msg = b"hello";
+
+        sign_and_verify(&sk, msg).unwrap();
+        assert_eq!(Keypair::verifying_key(&sk), sk.public_key());
+
+        let sig: Signature = Signer::sign(&sk, msg);
+        assert_eq!(sig, sk.sign(msg));
+        assert!(Verifier::verify(&sk.public_key(), msg, &sig).is_ok());
+        assert!(Verifier::verify(&sk.public_key(), b"other", &sig).is_err());
+
+        let bytes = sig.to_bytes();
+        assert_eq!(bytes, sig.to_compressed());
+        assert_eq!(sig.encoded_len(), 96);
+        assert_eq!(sig.to_vec(), sig.as_bytes());
+        assert_eq!(Signature::try_from(&bytes[..]).unwrap(), sig);
+        assert!(Signature::try_from(&bytes[1..]).is_err());
+    }
+
     #[test]
     fn test_bytes_roundtrip() {
         let rng = &mut ChaCha8Rng::seed_from_u64(12);
@@ -281,12 +662,74 @@ mod tests {
 
         assert_eq!(sk_bytes.len(), 32);
         assert_eq!(PrivateKey::from_bytes(&sk_bytes).unwrap(), sk);
+        assert_eq!(&sk.to_bytes()[..], &sk_bytes[..]);
+        assert_eq!(PrivateKey::from_byte_array(&sk.to_bytes()).unwrap(), sk);
+        assert!(PrivateKey::from_bytes(&sk_bytes[1..]).is_err());
 
         let pk = sk.public_key();
         let pk_bytes = pk.as_bytes();
 
         assert_eq!(pk_bytes.len(), 48);
         assert_eq!(PublicKey::from_bytes(&pk_bytes).unwrap(), pk);
+        assert_eq!(&pk.to_compressed()[..], &pk_bytes[..]);
+        assert_eq!(PublicKey::from_compressed(&pk.to_compressed()).unwrap(), pk);
+        assert!(PublicKey::from_bytes(&pk_bytes[1..]).is_err());
+
+        #[cfg(feature = "std")]
+        {
+            let mut written = Vec::new();
+            pk.write_bytes(&mut written).unwrap();
+            assert_eq!(written, pk_bytes);
+
+            let mut written = Vec::new();
+            sk.write_bytes(&mut written).unwrap();
+            assert_eq!(written, sk_bytes);
+        }
+    }
+
+    #[test]
+    fn test_uncompressed() {
+        let rng = &mut ChaCha8Rng::seed_from_u64(12);
+        let pk = PrivateKey::generate(rng).public_key();
+        let raw = pk.to_uncompressed();
+
+        assert_eq!(raw.len(), 96);
+        assert_eq!(PublicKey::from_uncompressed(&raw).unwrap(), pk);
+        assert_eq!(PublicKey::from_uncompressed_unchecked(&raw).unwrap(), pk);
+
+        // not on the curve
+        let mut invalid = raw;
+        invalid[95] ^= 1;
+        assert!(PublicKey::from_uncompressed(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let rng = &mut ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(rng);
+        let pk = sk.public_key();
+
+        // hex strings in human readable formats
+        let json = serde_json::to_string(&pk).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(pk.as_bytes())));
+        assert_eq!(serde_json::from_str::<PublicKey>(&json).unwrap(), pk);
+        let json = serde_json::to_string(&sk).unwrap();
+        assert_eq!(serde_json::from_str::<PrivateKey>(&json).unwrap(), sk);
+
+        // raw bytes otherwise
+        let encoded = bincode::serialize(&pk).unwrap();
+        assert_eq!(&encoded[8..], &pk.as_bytes()[..]);
+        assert_eq!(bincode::deserialize::<PublicKey>(&encoded).unwrap(), pk);
+        let encoded = bincode::serialize(&sk).unwrap();
+        assert_eq!(bincode::deserialize::<PrivateKey>(&encoded).unwrap(), sk);
+
+        // decoding runs the same checks as `from_bytes`
+        let zero_key = PublicKey(G1Projective::identity());
+        let json = serde_json::to_string(&zero_key).unwrap();
+        assert!(serde_json::from_str::<PublicKey>(&json).is_ok());
+        assert!(serde_json::from_str::<ValidatedPublicKey>(&json).is_err());
+        assert!(serde_json::from_str::<PrivateKey>("\"00\"").is_err());
+        assert!(serde_json::from_str::<PublicKey>("\"zz\"").is_err());
     }
 
     #[test]
@@ -313,6 +756,29 @@ mod tests {
         assert_eq!(fr_val, expect);
     }
 
+    static_assertions::assert_not_impl_any!(PrivateKey: Copy);
+    static_assertions::assert_impl_all!(PrivateKey: ZeroizeOnDrop, ConstantTimeEq, Eq);
+
+    #[test]
+    fn test_secret_handling() {
+        let rng = &mut ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(rng);
+        let other = PrivateKey::generate(rng);
+
+        assert_eq!(format!("{:?}", sk), "PrivateKey(..)");
+        assert!(bool::from(sk.ct_eq(&sk.clone())));
+        assert!(!bool::from(sk.ct_eq(&other)));
+        assert_ne!(sk, other);
+
+        let mut repr = ScalarRepr::from(&sk);
+        repr.0.zeroize();
+        assert!(repr.0.iter().all(|digit| *digit == 0));
+
+        let mut scalar = sk.0;
+        zeroize_scalar(&mut scalar);
+        assert!(bool::from(scalar.is_zero()));
+    }
+
     #[test]
     fn test_sig() {
         let msg = "this is the message";
@@ -325,6 +791,31 @@ mod tests {
         assert!(pk.verify(sig, msg));
     }
 
+    #[test]
+    fn test_pop() {
+        let rng = &mut ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(rng);
+        let pk = sk.public_key();
+        let proof = sk.pop_prove();
+
+        assert!(pk.pop_verify(proof));
+
+        // a proof does not transfer to another key
+        let other = PrivateKey::generate(rng).public_key();
+        assert!(!other.pop_verify(proof));
+
+        // nor is it a signature over the encoded public key
+        let sig = sk.sign(pk.as_bytes());
+        let sig_as_proof = ProofOfPossession::from_bytes(&sig.as_bytes()).unwrap();
+        assert!(!pk.pop_verify(sig_as_proof));
+        let proof_as_sig = Signature::from_bytes(&proof.as_bytes()).unwrap();
+        assert!(!pk.verify(proof_as_sig, pk.as_bytes()));
+
+        // the zero key, which can only be built inside the crate, has no valid proof
+        let zero_key = PrivateKey(Scalar::zero());
+        assert!(!zero_key.public_key().pop_verify(zero_key.pop_prove()));
+    }
+
     #[test]
     fn test_from_bytes() {
         // Larger than the modulus
@@ -0,0 +1,527 @@
+//! Password protected storage of private keys in the JSON format of
+//! EIP-2335: https://eips.ethereum.org/EIPS/eip-2335
+
+use aes::{
+    cipher::{NewCipher, StreamCipher},
+    Aes128Ctr,
+};
+use hmac::Hmac;
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize as SerdeSerialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+use crate::error::Error;
+use crate::key::{PrivateKey, PublicKey, Serialize};
+
+const VERSION: u32 = 4;
+
+/// Length of the derived key, the first half keys the cipher, the second half the checksum.
+const DKLEN: u32 = 32;
+const SALT_LEN: usize = 32;
+const IV_LEN: usize = 16;
+const SECRET_LEN: usize = 32;
+
+/// Upper bounds of the KDF parameters, so that a crafted keystore can't make decryption take
+/// unbounded time or memory. They leave ample room above the parameters suggested by the EIP.
+const MAX_SCRYPT_N: u32 = 1 << 20;
+const MAX_SCRYPT_RP: u64 = 1 << 6;
+/// scrypt needs `128 * n * r` bytes of memory.
+const MAX_SCRYPT_MEMORY: u64 = 1 << 30;
+const MAX_PBKDF2_C: u32 = 1 << 24;
+
+/// Key derivation function used to turn the password into the decryption key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Kdf {
+    /// scrypt with cost `n`, block size `r` and parallelization `p`.
+    Scrypt { n: u32, r: u32, p: u32 },
+    /// PBKDF2 with HMAC-SHA256 and `c` iterations.
+    Pbkdf2 { c: u32 },
+}
+
+impl Default for Kdf {
+    /// scrypt with the parameters suggested by the EIP.
+    fn default() -> Self {
+        Kdf::Scrypt {
+            n: 262144,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// An encrypted private key.
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+pub struct Keystore {
+    crypto: Crypto,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    pubkey: String,
+    path: String,
+    uuid: String,
+    version: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+struct Crypto {
+    kdf: KdfModule,
+    checksum: ChecksumModule,
+    cipher: CipherModule,
+}
+
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+struct KdfModule {
+    #[serde(flatten)]
+    params: KdfParams,
+    message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+#[serde(tag = "function", content = "params", rename_all = "lowercase")]
+enum KdfParams {
+    Scrypt {
+        dklen: u32,
+        n: u32,
+        r: u32,
+        p: u32,
+        #[serde(with = "hex")]
+        salt: Vec<u8>,
+    },
+    Pbkdf2 {
+        dklen: u32,
+        c: u32,
+        prf: String,
+        #[serde(with = "hex")]
+        salt: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+#[serde(tag = "function", content = "params")]
+enum ChecksumParams {
+    #[serde(rename = "sha256")]
+    Sha256 {},
+}
+
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+struct ChecksumModule {
+    #[serde(flatten)]
+    params: ChecksumParams,
+    #[serde(with = "hex")]
+    message: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+#[serde(tag = "function", content = "params")]
+enum CipherParams {
+    #[serde(rename = "aes-128-ctr")]
+    Aes128Ctr {
+        #[serde(with = "hex")]
+        iv: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, SerdeSerialize, Deserialize)]
+struct CipherModule {
+    #[serde(flatten)]
+    params: CipherParams,
+    #[serde(with = "hex")]
+    message: Vec<u8>,
+}
+
+impl Keystore {
+    /// Encrypts the private key under the given password. `path` is the derivation path of the
+    /// key, and may be empty.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        private_key: &PrivateKey,
+        password: &str,
+        path: &str,
+        kdf: Kdf,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let mut salt = vec![0u8; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        let mut iv = vec![0u8; IV_LEN];
+        rng.fill_bytes(&mut iv);
+
+        let kdf = match kdf {
+            Kdf::Scrypt { n, r, p } => KdfParams::Scrypt {
+                dklen: DKLEN,
+                n,
+                r,
+                p,
+                salt,
+            },
+            Kdf::Pbkdf2 { c } => KdfParams::Pbkdf2 {
+                dklen: DKLEN,
+                c,
+                prf: "hmac-sha256".to_string(),
+                salt,
+            },
+        };
+        let decryption_key = kdf.derive(&normalize_password(password))?;
+
+        // the secret is stored as a big endian integer
+        let mut secret = Zeroizing::new(private_key.to_bytes());
+        secret.reverse();
+        apply_cipher(&decryption_key, &iv, &mut secret[..])?;
+        let message = secret.to_vec();
+
+        let checksum = checksum(&decryption_key, &message);
+
+        Ok(Keystore {
+            crypto: Crypto {
+                kdf: KdfModule {
+                    params: kdf,
+                    message: String::new(),
+                },
+                checksum: ChecksumModule {
+                    params: ChecksumParams::Sha256 {},
+                    message: checksum,
+                },
+                cipher: CipherModule {
+                    params: CipherParams::Aes128Ctr { iv },
+                    message,
+                },
+            },
+            description: String::new(),
+            pubkey: hex::encode(private_key.public_key().as_bytes()),
+            path: path.to_string(),
+            uuid: uuid_v4(rng),
+            version: VERSION,
+        })
+    }
+
+    /// Decrypts the private key, fails with `Error::InvalidPassword` if the checksum does not
+    /// match.
+    pub fn decrypt(&self, password: &str) -> Result<PrivateKey, Error> {
+        if self.version != VERSION {
+            return Err(Error::InvalidKeystore);
+        }
+
+        let decryption_key = self
+            .crypto
+            .kdf
+            .params
+            .derive(&normalize_password(password))?;
+
+        let ciphertext = &self.crypto.cipher.message;
+        let checksum = checksum(&decryption_key, ciphertext);
+        if !bool::from(checksum.ct_eq(&self.crypto.checksum.message)) {
+            return Err(Error::InvalidPassword);
+        }
+
+        let CipherParams::Aes128Ctr { iv } = &self.crypto.cipher.params;
+        let mut secret = Zeroizing::new(ciphertext.clone());
+        apply_cipher(&decryption_key, iv, &mut secret)?;
+        if secret.len() != SECRET_LEN {
+            return Err(Error::InvalidKeystore);
+        }
+
+        secret.reverse();
+        let private_key = PrivateKey::from_bytes(&secret)?;
+
+        if !self.pubkey.is_empty() && self.public_key()? != private_key.public_key() {
+            return Err(Error::InvalidKeystore);
+        }
+
+        Ok(private_key)
+    }
+
+    /// Parses a keystore from its JSON encoding.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Encodes the keystore as JSON.
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The public key matching the encrypted private key.
+    pub fn public_key(&self) -> Result<PublicKey, Error> {
+        let raw = hex::decode(&self.pubkey).map_err(|_| Error::InvalidKeystore)?;
+
+        PublicKey::from_bytes(&raw)
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn set_description<T: Into<String>>(&mut self, description: T) {
+        self.description = description.into();
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+}
+
+impl KdfParams {
+    fn derive(&self, password: &[u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let mut decryption_key = Zeroizing::new(vec![0u8; DKLEN as usize]);
+
+        match self {
+            KdfParams::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                if *dklen != DKLEN || !n.is_power_of_two() || *n > MAX_SCRYPT_N {
+                    return Err(Error::InvalidKeystore);
+                }
+                if u64::from(*r) * u64::from(*p) > MAX_SCRYPT_RP
+                    || 128 * u64::from(*n) * u64::from(*r) > MAX_SCRYPT_MEMORY
+                {
+                    return Err(Error::InvalidKeystore);
+                }
+
+                let params = scrypt::Params::new(n.trailing_zeros() as u8, *r, *p)
+                    .map_err(|_| Error::InvalidKeystore)?;
+                scrypt::scrypt(password, salt, &params, &mut decryption_key)
+                    .map_err(|_| Error::InvalidKeystore)?;
+            }
+            KdfParams::Pbkdf2 {
+                dklen,
+                c,
+                prf,
+                salt,
+            } => {
+                if *dklen != DKLEN || prf != "hmac-sha256" || *c > MAX_PBKDF2_C {
+                    return Err(Error::InvalidKeystore);
+                }
+
+                pbkdf2::pbkdf2::<Hmac<Sha256>>(password, salt, *c, &mut decryption_key);
+            }
+        }
+
+        Ok(decryption_key)
+    }
+}
+
+/// Encrypts or decrypts the message in place, keyed by the first half of the decryption key.
+fn apply_cipher(decryption_key: &[u8], iv: &[u8], message: &mut [u8]) -> Result<(), Error> {
+    let mut cipher = Aes128Ctr::new_from_slices(&decryption_key[..16], iv)
+        .map_err(|_| Error::InvalidKeystore)?;
+    cipher.apply_keystream(message);
+
+    Ok(())
+}
+
+/// Calculated by `SHA256(decryption_key[16..32] || cipher_message)`.
+fn checksum(decryption_key: &[u8], cipher_message: &[u8]) -> Vec<u8> {
+    Sha256::new()
+        .chain(&decryption_key[16..32])
+        .chain(cipher_message)
+        .finalize()
+        .to_vec()
+}
+
+/// Converts the password to its NFKD representation and strips the C0, C1 and Delete control
+/// codes.
+fn normalize_password(password: &str) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(
+        password
+            .nfkd()
+            .filter(|c| !matches!(*c as u32, 0x00..=0x1f | 0x7f..=0x9f))
+            .collect::<String>()
+            .into_bytes(),
+    )
+}
+
+/// Random UUID as in RFC 4122.
+fn uuid_v4<R: RngCore>(rng: &mut R) -> String {
+    let mut bytes = [0u8; 16];
+    rng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const PASSWORD: &str = "𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑";
+    const SECRET: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+
+    // https://eips.ethereum.org/EIPS/eip-2335#test-cases
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "scrypt",
+                "params": {
+                    "dklen": 32,
+                    "n": 262144,
+                    "p": 1,
+                    "r": 8,
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "d2217fe5f3e9a1e34581ef8a78f7c9928e436d36dacc5e846690a5581e8ea484"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "06ae90d55fe0a6e9c5c3bc5b170827b2e5cce3929ed3f116c2811e6366dfe20f"
+            }
+        },
+        "description": "This is a test keystore that uses scrypt to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/3141592653/589793238",
+        "uuid": "1d85ae20-35c5-4611-98e8-aa14a633906f",
+        "version": 4
+    }"#;
+
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "dklen": 32,
+                    "c": 262144,
+                    "prf": "hmac-sha256",
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+            }
+        },
+        "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/0/0",
+        "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+        "version": 4
+    }"#;
+
+    fn secret() -> PrivateKey {
+        let mut raw = hex::decode(SECRET).unwrap();
+        raw.reverse();
+        PrivateKey::from_bytes(&raw).unwrap()
+    }
+
+    #[test]
+    fn test_vectors() {
+        for json in &[SCRYPT_KEYSTORE, PBKDF2_KEYSTORE] {
+            let keystore = Keystore::from_json(json).unwrap();
+            assert_eq!(keystore.decrypt(PASSWORD).unwrap(), secret());
+            assert_eq!(keystore.public_key().unwrap(), secret().public_key());
+            assert!(matches!(
+                keystore.decrypt("testpassword"),
+                Err(Error::InvalidPassword)
+            ));
+
+            // encoding is lossless
+            let reencoded = Keystore::from_json(&keystore.to_json().unwrap()).unwrap();
+            assert_eq!(reencoded, keystore);
+        }
+    }
+
+    #[test]
+    fn test_password_normalization() {
+        assert_eq!(
+            *normalize_password(PASSWORD),
+            hex::decode("7465737470617373776f7264f09f9491").unwrap()
+        );
+        assert_eq!(
+            *normalize_password("\u{0}test\u{1f}pass\u{7f}word\u{80}\u{9f}"),
+            b"testpassword"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+
+        for kdf in &[Kdf::Scrypt { n: 16, r: 8, p: 1 }, Kdf::Pbkdf2 { c: 16 }] {
+            let mut keystore =
+                Keystore::encrypt(&sk, "password", "m/12381/3600/0/0/0", *kdf, &mut rng).unwrap();
+            keystore.set_description("test");
+
+            let keystore = Keystore::from_json(&keystore.to_json().unwrap()).unwrap();
+            assert_eq!(keystore.decrypt("password").unwrap(), sk);
+            assert_eq!(keystore.path(), "m/12381/3600/0/0/0");
+            assert_eq!(keystore.description(), "test");
+            assert_eq!(keystore.uuid().len(), 36);
+            assert!(keystore.decrypt("passw0rd").is_err());
+        }
+
+        // unsupported parameters
+        for kdf in &[
+            Kdf::Scrypt { n: 15, r: 8, p: 1 },
+            Kdf::Scrypt {
+                n: 1 << 21,
+                r: 8,
+                p: 1,
+            },
+            Kdf::Scrypt {
+                n: 1 << 20,
+                r: 16,
+                p: 1,
+            },
+            Kdf::Scrypt { n: 16, r: 8, p: 16 },
+            Kdf::Pbkdf2 { c: (1 << 24) + 1 },
+        ] {
+            assert!(
+                matches!(
+                    Keystore::encrypt(&sk, "", "", *kdf, &mut rng),
+                    Err(Error::InvalidKeystore)
+                ),
+                "{:?}",
+                kdf
+            );
+        }
+
+        // decryption rejects them before running the KDF
+        let mut json: serde_json::Value = serde_json::from_str(
+            &Keystore::encrypt(&sk, "", "", Kdf::Pbkdf2 { c: 16 }, &mut rng)
+                .unwrap()
+                .to_json()
+                .unwrap(),
+        )
+        .unwrap();
+        json["crypto"]["kdf"]["params"]["c"] = u32::MAX.into();
+        let keystore = Keystore::from_json(&json.to_string()).unwrap();
+        assert!(matches!(keystore.decrypt(""), Err(Error::InvalidKeystore)));
+    }
+}
@@ -172,25 +172,45 @@
 //! [`curve`]: ./curve/index.html
 //! [`SignatureSchemeExt`]: ./sig/trait.SignatureSchemeExt.html*/
 
+// The arkworks based modules are behind the `threshold` feature, and without `std` the rest only
+// needs `alloc`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "pairing", feature = "blst")))]
+compile_error!("one of the `pairing` or `blst` features must be enabled");
+
 /// Curve implementations for the traits defined in the [`group`](group/index.html) module.
+#[cfg(feature = "threshold")]
 pub mod curve;
 
 /// Elliptic Curve Integrated Encryption Scheme using SHA256 as the Key Derivation
+#[cfg(feature = "threshold")]
 pub mod ecies;
 
 /// Definitions of generic traits with scalars of prime fields and points on elliptic curves.
+#[cfg(feature = "threshold")]
 pub mod group;
 
 /// Implementation of a polynomial suitable to be used for secret sharing schemes and DKG
 /// protocols. It can evaluate and interpolate private and public shares to their corresponding
 /// polynomial.
+#[cfg(feature = "threshold")]
 pub mod poly;
 
 /// BLS Signature implementations. Supports blind and threshold signatures.
+#[cfg(feature = "threshold")]
 pub mod sig;
 
+pub mod bdn;
+pub mod decode;
+pub mod eip2333;
 pub mod error;
 pub mod key;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+pub mod min_sig;
 pub mod sig_aggregate;
 
 #[cfg(test)]
@@ -198,6 +218,7 @@ pub mod sig_aggregate;
 extern crate base64_serde;
 
 /// Pre-instantiated signature schemes for each curve
+#[cfg(feature = "threshold")]
 pub mod schemes {
     use crate::sig::{G1Scheme, G2Scheme};
 
@@ -215,11 +236,19 @@ pub mod schemes {
     /// BLS12-381 Schemes
     pub mod bls12_381 {
         use crate::curve::bls12381::PairingCurve;
+        use crate::group::Legacy;
         pub use crate::curve::bls12381::{G1Curve, G2Curve};
 
         /// Public Keys on G1, Signatures on G2
         pub type G1Scheme = super::G1Scheme<PairingCurve>;
         /// Public Keys on G2, Signatures on G1
         pub type G2Scheme = super::G2Scheme<PairingCurve>;
+        /// Public Keys on G1, Signatures on G2, compatible with `sig_aggregate`
+        pub use crate::sig::SigAggregateScheme;
+
+        /// `G1Scheme` hashing with try-and-increment, as before RFC 9380 support
+        pub type LegacyG1Scheme = super::G1Scheme<PairingCurve, Legacy>;
+        /// `G2Scheme` hashing with try-and-increment, as before RFC 9380 support
+        pub type LegacyG2Scheme = super::G2Scheme<PairingCurve, Legacy>;
     }
 }
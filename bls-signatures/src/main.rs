@@ -1,8 +1,8 @@
 use std::time::{Duration, Instant};
 
 use bls_signatures::{
-    key::{PrivateKey, PublicKey, Serialize},
-    sig_aggregate::{aggregate, hash, verify, verify_messages, Signature}
+    key::{PrivateKey, ValidatedPublicKey},
+    sig_aggregate::{aggregate, fast_aggregate_verify, hash, verify, verify_messages, Signature, ValidatedSignature}
  };
 
 #[cfg(feature = "pairing")]
@@ -99,11 +99,11 @@ macro_rules! measure {
               .map(|message| hash(message))
               .collect::<Vec<_>>();
       });
-      let public_keys: Vec<PublicKey>;
+      let public_keys: Vec<ValidatedPublicKey>;
       measure!("extracting public keys", num_messages, {
           public_keys = private_keys
               .par_iter()
-              .map(|pk| pk.public_key())
+              .map(|pk| ValidatedPublicKey::new_unchecked(pk.public_key()))
               .collect::<Vec<_>>();
       });
   
@@ -111,6 +111,7 @@ macro_rules! measure {
     //   measure!("deserialize signature", {
     //       agg_sig = Signature::from_bytes(&serialized_signature).unwrap();
     //   });
+      let aggregated_signature = ValidatedSignature::new_unchecked(aggregated_signature);
   
       measure!("verification", num_messages, {
           assert!(verify(&aggregated_signature, &hashes, &public_keys));
@@ -130,7 +131,7 @@ macro_rules! measure {
     measure!("signing", num_messages, {
         sigs = private_keys
             .par_iter()
-            .map(|pk| pk.sign(&message))
+            .map(|pk| pk.sign_pop(&message))
             .collect::<Vec<Signature>>();
     });
 
@@ -153,7 +154,7 @@ macro_rules! measure {
     measure!("extracting public keys", num_messages, {
         public_keys = private_keys
             .par_iter()
-            .map(|pk| pk.public_key())
+            .map(|pk| ValidatedPublicKey::new_unchecked(pk.public_key()))
             .collect::<Vec<_>>();
     });
 
@@ -161,6 +162,7 @@ macro_rules! measure {
     //   measure!("deserialize signature", {
     //       agg_sig = Signature::from_bytes(&serialized_signature).unwrap();
     //   });
+    let aggregated_signature = ValidatedSignature::new_unchecked(aggregated_signature);
 
       measure!("verification", num_messages, {
         assert!(
@@ -179,5 +181,13 @@ macro_rules! measure {
             &public_keys
         ));
     });
+
+    measure!("fast aggregate verification", num_messages, {
+        assert!(fast_aggregate_verify(
+            &aggregated_signature,
+            &message,
+            &public_keys
+        ));
+    });
  }    
  
\ No newline at end of file
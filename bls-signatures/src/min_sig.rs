@@ -0,0 +1,1064 @@
+//! Minimal-signature-size variant of the BLS signature scheme, with public keys in G2 and
+//! signatures in G1. See Section 2.1 of the IRTF's BLS signatures spec:
+//! https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.1
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::{TryFrom, TryInto};
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "multicore")]
+use rayon::prelude::*;
+
+#[cfg(feature = "pairing")]
+use bls12_381::{
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+    Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, MillerLoopResult,
+};
+use group::Curve;
+use pairing_lib::MultiMillerLoop;
+use signature::{SignatureEncoding, Signer, Verifier};
+
+#[cfg(feature = "blst")]
+use blstrs::{
+    Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, MillerLoopResult,
+};
+#[cfg(feature = "blst")]
+use group::{prime::PrimeCurveAffine, Group};
+#[cfg(feature = "blst")]
+use pairing_lib::MillerLoopResult as _;
+
+use crate::error::{Error, VerificationError};
+use crate::key::{impl_serde, PrivateKey, Serialize, G1_COMPRESSED_SIZE, G1_UNCOMPRESSED_SIZE};
+use crate::sig_aggregate::{g2_from_slice, G2_COMPRESSED_SIZE, G2_UNCOMPRESSED_SIZE};
+
+const CSUITE: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PublicKey(pub(crate) G2Projective);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Signature(G1Affine);
+
+impl From<G2Projective> for PublicKey {
+    fn from(val: G2Projective) -> Self {
+        PublicKey(val)
+    }
+}
+impl From<PublicKey> for G2Projective {
+    fn from(val: PublicKey) -> Self {
+        val.0
+    }
+}
+
+impl From<G1Projective> for Signature {
+    fn from(val: G1Projective) -> Self {
+        Signature(val.into())
+    }
+}
+impl From<Signature> for G1Projective {
+    fn from(val: Signature) -> Self {
+        val.0.into()
+    }
+}
+
+impl From<G1Affine> for Signature {
+    fn from(val: G1Affine) -> Self {
+        Signature(val)
+    }
+}
+
+impl From<Signature> for G1Affine {
+    fn from(val: Signature) -> Self {
+        val.0
+    }
+}
+
+impl PrivateKey {
+    /// Sign the given message in G1.
+    /// Calculated by `signature = hash_into_g1(message) * sk`
+    #[cfg(feature = "pairing")]
+    pub fn sign_min_sig<T: AsRef<[u8]>>(&self, message: T) -> Signature {
+        let mut p = hash(message.as_ref());
+        p *= self.0;
+
+        p.into()
+    }
+
+    /// Sign the given message in G1.
+    /// Calculated by `signature = hash_into_g1(message) * sk`
+    #[cfg(feature = "blst")]
+    pub fn sign_min_sig<T: AsRef<[u8]>>(&self, message: T) -> Signature {
+        let p = hash(message.as_ref());
+        let mut sig = G1Affine::identity();
+
+        unsafe {
+            blst_lib::blst_sign_pk2_in_g2(
+                core::ptr::null_mut(),
+                sig.as_mut(),
+                p.as_ref(),
+                &self.0.into(),
+            );
+        }
+
+        sig.into()
+    }
+
+    /// Get the G2 public key for this private key.
+    /// Calculated by `pk = g2 * sk`.
+    #[cfg(feature = "pairing")]
+    pub fn public_key_min_sig(&self) -> PublicKey {
+        let mut pk = G2Projective::generator();
+        pk *= self.0;
+
+        PublicKey(pk)
+    }
+
+    /// Get the G2 public key for this private key.
+    /// Calculated by `pk = g2 * sk`.
+    #[cfg(feature = "blst")]
+    pub fn public_key_min_sig(&self) -> PublicKey {
+        let mut pk = G2Affine::identity();
+
+        unsafe {
+            blst_lib::blst_sk_to_pk2_in_g2(core::ptr::null_mut(), pk.as_mut(), &self.0.into());
+        }
+
+        PublicKey(pk.into())
+    }
+}
+
+impl PublicKey {
+    pub fn as_affine(&self) -> G2Affine {
+        self.0.to_affine()
+    }
+
+    /// Encodes the key with point compression, as `as_bytes` does.
+    pub fn to_compressed(&self) -> [u8; G2_COMPRESSED_SIZE] {
+        self.as_affine().to_compressed()
+    }
+
+    /// Decodes a key produced by `to_compressed`, checking that it is on the curve and in the
+    /// prime-order subgroup.
+    pub fn from_compressed(raw: &[u8; G2_COMPRESSED_SIZE]) -> Result<Self, Error> {
+        let affine: G2Affine =
+            Option::from(G2Affine::from_compressed(raw)).ok_or(Error::GroupDecode)?;
+
+        Ok(PublicKey(affine.into()))
+    }
+
+    /// Encodes the key without point compression, which is faster to decode.
+    pub fn to_uncompressed(&self) -> [u8; G2_UNCOMPRESSED_SIZE] {
+        self.as_affine().to_uncompressed()
+    }
+
+    /// Decodes a key produced by `to_uncompressed`, checking that it is on the curve and in
+    /// the prime-order subgroup.
+    pub fn from_uncompressed(raw: &[u8; G2_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        let affine: G2Affine =
+            Option::from(G2Affine::from_uncompressed(raw)).ok_or(Error::GroupDecode)?;
+
+        Ok(PublicKey(affine.into()))
+    }
+
+    /// Decodes a key produced by `to_uncompressed` without any checks, only for trusted
+    /// sources such as a local cache.
+    pub fn from_uncompressed_unchecked(raw: &[u8; G2_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        let affine: G2Affine =
+            Option::from(G2Affine::from_uncompressed_unchecked(raw)).ok_or(Error::GroupDecode)?;
+
+        Ok(PublicKey(affine.into()))
+    }
+
+    /// Runs KeyValidate, rejecting the identity and points outside of the prime-order subgroup.
+    pub fn validate(&self) -> Result<ValidatedPublicKey, Error> {
+        let affine = self.as_affine();
+        if bool::from(affine.is_identity()) || !bool::from(affine.is_torsion_free()) {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        Ok(ValidatedPublicKey(*self))
+    }
+
+    /// Verifies the signature, after validating both the key and the signature.
+    pub fn verify<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> bool {
+        self.try_verify(sig, message).is_ok()
+    }
+
+    /// Like `verify`, but reports why verification failed.
+    pub fn try_verify<T: AsRef<[u8]>>(&self, sig: Signature, message: T) -> Result<(), Error> {
+        let public_key = self.validate()?;
+        let sig = sig.validate()?;
+
+        try_verify_messages(&sig, &[message.as_ref()], &[public_key])
+    }
+}
+
+impl Signature {
+    /// Encodes the signature with point compression, as `as_bytes` does.
+    pub fn to_compressed(&self) -> [u8; G1_COMPRESSED_SIZE] {
+        self.0.to_compressed()
+    }
+
+    /// Decodes a signature produced by `to_compressed`, checking that it is on the curve and in
+    /// the prime-order subgroup.
+    pub fn from_compressed(raw: &[u8; G1_COMPRESSED_SIZE]) -> Result<Self, Error> {
+        Option::from(G1Affine::from_compressed(raw))
+            .map(Signature)
+            .ok_or(Error::GroupDecode)
+    }
+
+    /// Encodes the signature without point compression, which is faster to decode.
+    pub fn to_uncompressed(&self) -> [u8; G1_UNCOMPRESSED_SIZE] {
+        self.0.to_uncompressed()
+    }
+
+    /// Decodes a signature produced by `to_uncompressed`, checking that it is on the curve and
+    /// in the prime-order subgroup.
+    pub fn from_uncompressed(raw: &[u8; G1_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        Option::from(G1Affine::from_uncompressed(raw))
+            .map(Signature)
+            .ok_or(Error::GroupDecode)
+    }
+
+    /// Decodes a signature produced by `to_uncompressed` without any checks, only for trusted
+    /// sources such as a local cache.
+    pub fn from_uncompressed_unchecked(raw: &[u8; G1_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        Option::from(G1Affine::from_uncompressed_unchecked(raw))
+            .map(Signature)
+            .ok_or(Error::GroupDecode)
+    }
+
+    /// Rejects signatures outside of the prime-order subgroup.
+    pub fn validate(&self) -> Result<ValidatedSignature, Error> {
+        if !bool::from(self.0.is_on_curve()) || !bool::from(self.0.is_torsion_free()) {
+            return Err(Error::InvalidSignature);
+        }
+
+        Ok(ValidatedSignature(*self))
+    }
+}
+
+// `Keypair` is only implemented for the G1 keys of the main scheme, as `PrivateKey` can have a
+// single verifying key type.
+impl Signer<Signature> for PrivateKey {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(self.sign_min_sig(msg))
+    }
+}
+
+impl Verifier<Signature> for PublicKey {
+    fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), signature::Error> {
+        Ok(self.try_verify(*signature, msg)?)
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = Error;
+
+    fn try_from(raw: &[u8]) -> Result<Self, Error> {
+        Signature::from_bytes(raw)
+    }
+}
+
+impl From<Signature> for [u8; G1_COMPRESSED_SIZE] {
+    fn from(val: Signature) -> Self {
+        val.to_compressed()
+    }
+}
+
+impl SignatureEncoding for Signature {
+    type Repr = [u8; G1_COMPRESSED_SIZE];
+}
+
+/// A G2 public key that passed KeyValidate, as required by all verification functions.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ValidatedPublicKey(PublicKey);
+
+impl ValidatedPublicKey {
+    /// Skips KeyValidate, only for keys from a trusted source that were validated before.
+    pub fn new_unchecked(public_key: PublicKey) -> Self {
+        ValidatedPublicKey(public_key)
+    }
+}
+
+impl Deref for ValidatedPublicKey {
+    type Target = PublicKey;
+
+    fn deref(&self) -> &PublicKey {
+        &self.0
+    }
+}
+
+impl From<ValidatedPublicKey> for PublicKey {
+    fn from(val: ValidatedPublicKey) -> Self {
+        val.0
+    }
+}
+
+impl TryFrom<PublicKey> for ValidatedPublicKey {
+    type Error = Error;
+
+    fn try_from(val: PublicKey) -> Result<Self, Error> {
+        val.validate()
+    }
+}
+
+/// A G1 signature that passed the subgroup check, as required by all verification functions.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ValidatedSignature(Signature);
+
+impl ValidatedSignature {
+    /// Skips the subgroup check, only for signatures from a trusted source.
+    pub fn new_unchecked(signature: Signature) -> Self {
+        ValidatedSignature(signature)
+    }
+}
+
+impl Deref for ValidatedSignature {
+    type Target = Signature;
+
+    fn deref(&self) -> &Signature {
+        &self.0
+    }
+}
+
+impl From<ValidatedSignature> for Signature {
+    fn from(val: ValidatedSignature) -> Self {
+        val.0
+    }
+}
+
+impl TryFrom<Signature> for ValidatedSignature {
+    type Error = Error;
+
+    fn try_from(val: Signature) -> Result<Self, Error> {
+        val.validate()
+    }
+}
+
+impl Serialize for PublicKey {
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        let g2 = g2_from_slice(raw)?;
+        Ok(PublicKey(g2.into()))
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.to_compressed().to_vec()
+    }
+}
+
+impl Serialize for Signature {
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        let g1 = g1_from_slice(raw)?;
+        Ok(g1.into())
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.0.to_compressed().to_vec()
+    }
+}
+
+impl Serialize for ValidatedPublicKey {
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        PublicKey::from_bytes(raw)?.validate()
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes()
+    }
+}
+
+impl Serialize for ValidatedSignature {
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        Signature::from_bytes(raw)?.validate()
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes()
+    }
+}
+
+impl_serde!(PublicKey);
+impl_serde!(ValidatedPublicKey);
+impl_serde!(Signature);
+impl_serde!(ValidatedSignature);
+
+fn g1_from_slice(raw: &[u8]) -> Result<G1Affine, Error> {
+    let raw: &[u8; G1_COMPRESSED_SIZE] = raw.try_into().map_err(|_| Error::SizeMismatch)?;
+
+    Option::from(G1Affine::from_compressed(raw)).ok_or(Error::GroupDecode)
+}
+
+/// Hash the given message, as used in the signature.
+pub fn hash(msg: &[u8]) -> G1Projective {
+    hash_to_g1(msg, CSUITE)
+}
+
+#[cfg(feature = "pairing")]
+fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective {
+    <G1Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, dst)
+}
+
+#[cfg(feature = "blst")]
+fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective {
+    G1Projective::hash_to_curve(msg, dst, &[])
+}
+
+/// Aggregate signatures by multiplying them together.
+/// Calculated by `signature = \sum_{i = 0}^n signature_i`.
+#[cfg(feature = "multicore")]
+pub fn aggregate(signatures: &[Signature]) -> Result<Signature, Error> {
+    if signatures.is_empty() {
+        return Err(Error::ZeroSizedInput);
+    }
+
+    let res = signatures
+        .into_par_iter()
+        .fold(G1Projective::identity, |mut acc, signature| {
+            acc += &signature.0;
+            acc
+        })
+        .reduce(G1Projective::identity, |acc, val| acc + val);
+
+    Ok(Signature(res.into()))
+}
+
+/// Aggregate signatures by multiplying them together.
+/// Calculated by `signature = \sum_{i = 0}^n signature_i`.
+#[cfg(not(feature = "multicore"))]
+pub fn aggregate(signatures: &[Signature]) -> Result<Signature, Error> {
+    if signatures.is_empty() {
+        return Err(Error::ZeroSizedInput);
+    }
+
+    let res = signatures
+        .iter()
+        .fold(G1Projective::identity(), |acc, signature| acc + signature.0);
+
+    Ok(Signature(res.into()))
+}
+
+/// Verifies that the signature is the actual aggregated signature of hashes - pubkeys.
+/// Calculated by `e(signature, g2) == \prod_{i = 0}^n e(hash_i, pk_i)`.
+pub fn verify(
+    signature: &ValidatedSignature,
+    hashes: &[G1Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> bool {
+    try_verify(signature, hashes, public_keys).is_ok()
+}
+
+/// Like `verify`, but reports why verification failed.
+pub fn try_verify(
+    signature: &ValidatedSignature,
+    hashes: &[G1Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), Error> {
+    check_hashes(hashes, public_keys)?;
+
+    if !verify_pairing(signature, hashes, public_keys) {
+        return Err(VerificationError::InvalidSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that there is one public key per message, and that none of them is the identity.
+fn check_public_keys(
+    n_messages: usize,
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), VerificationError> {
+    if n_messages == 0 || public_keys.is_empty() {
+        return Err(VerificationError::Empty);
+    }
+
+    if n_messages != public_keys.len() {
+        return Err(VerificationError::LengthMismatch(
+            n_messages,
+            public_keys.len(),
+        ));
+    }
+
+    match public_keys
+        .iter()
+        .position(|pk| bool::from(pk.as_affine().is_identity()))
+    {
+        Some(i) => Err(VerificationError::IdentityPublicKey(i)),
+        None => Ok(()),
+    }
+}
+
+/// Checks the shape of a `verify` input and that all hashes are distinct.
+fn check_hashes(
+    hashes: &[G1Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), VerificationError> {
+    check_public_keys(hashes.len(), public_keys)?;
+
+    // Enforce that messages are distinct as a countermeasure against BLS's rogue-key attack.
+    // See Section 3.1. of the IRTF's BLS signatures spec:
+    // https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.1
+    let n_hashes = hashes.len();
+    for i in 0..(n_hashes - 1) {
+        for j in (i + 1)..n_hashes {
+            if hashes[i] == hashes[j] {
+                return Err(VerificationError::DuplicateMessage(i, j));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the shape of a `verify_messages` input and that all messages are distinct.
+fn check_messages(
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), VerificationError> {
+    check_public_keys(messages.len(), public_keys)?;
+
+    // Enforce that messages are distinct as a countermeasure against BLS's rogue-key attack.
+    // See Section 3.1. of the IRTF's BLS signatures spec:
+    // https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.1
+    let mut seen = BTreeMap::new();
+    for (j, msg) in messages.iter().enumerate() {
+        if let Some(i) = seen.insert(*msg, j) {
+            return Err(VerificationError::DuplicateMessage(i, j));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks `e(signature, g2) == \prod_{i = 0}^n e(hash_i, pk_i)` without any checks on the hashes.
+/// Callers must ensure `hashes` and `public_keys` are non-empty and of equal length.
+fn verify_pairing(
+    signature: &Signature,
+    hashes: &[G1Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> bool {
+    let is_valid = AtomicBool::new(true);
+
+    #[cfg(feature = "multicore")]
+    let mut ml = public_keys
+        .par_iter()
+        .zip(hashes.par_iter())
+        .map(|(pk, h)| {
+            if pk.as_affine().is_identity().into() {
+                is_valid.store(false, Ordering::Relaxed);
+            }
+            let pk = G2Prepared::from(pk.as_affine());
+            let h = G1Affine::from(h);
+            Bls12::multi_miller_loop(&[(&h, &pk)])
+        })
+        .reduce(MillerLoopResult::default, |acc, cur| acc + cur);
+
+    #[cfg(not(feature = "multicore"))]
+    let mut ml = public_keys
+        .iter()
+        .zip(hashes.iter())
+        .map(|(pk, h)| {
+            if pk.as_affine().is_identity().into() {
+                is_valid.store(false, Ordering::Relaxed);
+            }
+            let pk = G2Prepared::from(pk.as_affine());
+            let h = G1Affine::from(h);
+            Bls12::multi_miller_loop(&[(&h, &pk)])
+        })
+        .fold(MillerLoopResult::default(), |acc, cur| acc + cur);
+
+    if !is_valid.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    let sig_neg = -signature.0;
+
+    ml += Bls12::multi_miller_loop(&[(&sig_neg, &G2Affine::generator().into())]);
+
+    ml.final_exponentiation() == Gt::identity()
+}
+
+/// Verifies that the signature is the actual aggregated signature of messages - pubkeys.
+/// Calculated by `e(signature, g2) == \prod_{i = 0}^n e(hash_i, pk_i)`.
+pub fn verify_messages(
+    signature: &ValidatedSignature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> bool {
+    try_verify_messages(signature, messages, public_keys).is_ok()
+}
+
+/// Like `verify_messages`, but reports why verification failed.
+pub fn try_verify_messages(
+    signature: &ValidatedSignature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), Error> {
+    check_messages(messages, public_keys)?;
+
+    #[cfg(feature = "multicore")]
+    let hashes: Vec<_> = messages.par_iter().map(|msg| hash(msg)).collect();
+
+    #[cfg(not(feature = "multicore"))]
+    let hashes: Vec<_> = messages.iter().map(|msg| hash(msg)).collect();
+
+    if !verify_pairing(signature, &hashes, public_keys) {
+        return Err(VerificationError::InvalidSignature.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, RngCore, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[cfg(feature = "pairing")]
+    use bls12_381::Scalar;
+    #[cfg(feature = "blst")]
+    use blstrs::Scalar;
+    #[cfg(feature = "blst")]
+    use ff::Field;
+
+    #[test]
+    fn basic_aggregation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let num_messages = 10;
+
+        // generate private keys
+        let private_keys: Vec<_> = (0..num_messages)
+            .map(|_| PrivateKey::generate(&mut rng))
+            .collect();
+
+        // generate messages
+        let messages: Vec<Vec<u8>> = (0..num_messages)
+            .map(|_| (0..64).map(|_| rng.gen()).collect())
+            .collect();
+
+        // sign messages
+        let sigs = messages
+            .iter()
+            .zip(&private_keys)
+            .map(|(message, pk)| pk.sign_min_sig(message))
+            .collect::<Vec<Signature>>();
+
+        let aggregated_signature = aggregate(&sigs)
+            .expect("failed to aggregate")
+            .validate()
+            .unwrap();
+
+        let hashes = messages
+            .iter()
+            .map(|message| hash(message))
+            .collect::<Vec<_>>();
+        let public_keys = private_keys
+            .iter()
+            .map(|pk| pk.public_key_min_sig().validate().unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(
+            verify(&aggregated_signature, &hashes, &public_keys),
+            "failed to verify"
+        );
+
+        let messages = messages.iter().map(|r| &r[..]).collect::<Vec<_>>();
+        assert!(verify_messages(
+            &aggregated_signature,
+            &messages[..],
+            &public_keys
+        ));
+
+        // a single signature verifies on its own, but not for another message
+        assert!(public_keys[0].verify(sigs[0], messages[0]));
+        assert!(!public_keys[0].verify(sigs[0], messages[1]));
+    }
+
+    #[test]
+    fn verification_errors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let private_keys: Vec<_> = (0..3).map(|_| PrivateKey::generate(&mut rng)).collect();
+        let public_keys: Vec<_> = private_keys
+            .iter()
+            .map(|sk| sk.public_key_min_sig().validate().unwrap())
+            .collect();
+        let messages: Vec<&[u8]> = vec![b"zero", b"one", b"two"];
+        let sigs: Vec<_> = private_keys
+            .iter()
+            .zip(messages.iter())
+            .map(|(sk, msg)| sk.sign_min_sig(msg))
+            .collect();
+        let signature = aggregate(&sigs).unwrap().validate().unwrap();
+
+        assert!(try_verify_messages(&signature, &messages, &public_keys).is_ok());
+
+        let err = |res: Result<(), Error>| match res {
+            Err(Error::Verification(e)) => e,
+            res => panic!("unexpected result {:?}", res),
+        };
+
+        assert_eq!(
+            err(try_verify_messages(&signature, &[], &[])),
+            VerificationError::Empty
+        );
+        assert_eq!(
+            err(try_verify_messages(
+                &signature,
+                &messages,
+                &public_keys[..2]
+            )),
+            VerificationError::LengthMismatch(3, 2)
+        );
+        assert_eq!(
+            err(try_verify_messages(
+                &signature,
+                &[b"zero", b"one", b"zero"],
+                &public_keys
+            )),
+            VerificationError::DuplicateMessage(0, 2)
+        );
+        let mut zero_keys = public_keys.clone();
+        zero_keys[1] = ValidatedPublicKey::new_unchecked(PublicKey(G2Projective::identity()));
+        assert_eq!(
+            err(try_verify_messages(&signature, &messages, &zero_keys)),
+            VerificationError::IdentityPublicKey(1)
+        );
+        assert_eq!(
+            err(try_verify_messages(
+                &sigs[0].validate().unwrap(),
+                &messages,
+                &public_keys
+            )),
+            VerificationError::InvalidSignature
+        );
+
+        // the hash based variant reports the same errors
+        let hashes: Vec<_> = messages.iter().map(|msg| hash(msg)).collect();
+        assert!(try_verify(&signature, &hashes, &public_keys).is_ok());
+        assert_eq!(
+            err(try_verify(
+                &signature,
+                &[hashes[0], hashes[1], hashes[1]],
+                &public_keys
+            )),
+            VerificationError::DuplicateMessage(1, 2)
+        );
+
+        assert!(public_keys[0].try_verify(sigs[0], messages[0]).is_ok());
+        assert_eq!(
+            err(public_keys[0].try_verify(sigs[0], messages[1])),
+            VerificationError::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn aggregation_same_messages() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let num_messages = 10;
+
+        // generate private keys
+        let private_keys: Vec<_> = (0..num_messages)
+            .map(|_| PrivateKey::generate(&mut rng))
+            .collect();
+
+        // generate messages
+        let message: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+
+        // sign messages
+        let sigs = private_keys
+            .iter()
+            .map(|pk| pk.sign_min_sig(&message))
+            .collect::<Vec<Signature>>();
+
+        let aggregated_signature = aggregate(&sigs)
+            .expect("failed to aggregate")
+            .validate()
+            .unwrap();
+
+        let public_keys = private_keys
+            .iter()
+            .map(|pk| pk.public_key_min_sig().validate().unwrap())
+            .collect::<Vec<_>>();
+        let messages = vec![&message[..]; num_messages];
+
+        assert!(!verify_messages(
+            &aggregated_signature,
+            &messages[..],
+            &public_keys
+        ));
+    }
+
+    #[test]
+    fn test_zero_key() {
+        let zero_key = PrivateKey(Scalar::zero());
+        assert!(bool::from(zero_key.public_key_min_sig().0.is_identity()));
+
+        let msg = b"this is the message";
+        let signature = zero_key.sign_min_sig(msg);
+
+        assert!(!zero_key.public_key_min_sig().verify(signature, msg));
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+        let pk = sk.public_key_min_sig();
+        let sig = sk.sign_min_sig(b"hello");
+
+        let validated_pk = pk.validate().unwrap();
+        let validated_sig = sig.validate().unwrap();
+        assert_eq!(PublicKey::from(validated_pk), pk);
+        assert_eq!(Signature::from(validated_sig), sig);
+        assert_eq!(
+            ValidatedPublicKey::from_bytes(&pk.as_bytes()).unwrap(),
+            validated_pk
+        );
+        assert_eq!(
+            ValidatedSignature::from_bytes(&sig.as_bytes()).unwrap(),
+            validated_sig
+        );
+        assert!(verify_messages(
+            &validated_sig,
+            &[b"hello"],
+            &[validated_pk]
+        ));
+
+        let zero_key = PublicKey(G2Projective::identity());
+        assert!(matches!(zero_key.validate(), Err(Error::InvalidPublicKey)));
+
+        // points outside of the prime-order subgroup are rejected
+        let torsion_pk = loop {
+            let mut raw = [0u8; 96];
+            rng.fill_bytes(&mut raw);
+            raw[0] = (raw[0] & 0x1f) | 0x80;
+            let point = G2Affine::from_compressed_unchecked(&raw);
+            if point.is_some().into() {
+                let point = point.unwrap();
+                if !bool::from(point.is_torsion_free()) {
+                    break PublicKey(point.into());
+                }
+            }
+        };
+        assert!(matches!(
+            torsion_pk.validate(),
+            Err(Error::InvalidPublicKey)
+        ));
+        assert!(!torsion_pk.verify(sig, b"hello"));
+
+        let torsion_sig = loop {
+            let mut raw = [0u8; 48];
+            rng.fill_bytes(&mut raw);
+            raw[0] = (raw[0] & 0x1f) | 0x80;
+            let point = G1Affine::from_compressed_unchecked(&raw);
+            if point.is_some().into() {
+                let point = point.unwrap();
+                if !bool::from(point.is_torsion_free()) {
+                    break Signature::from(point);
+                }
+            }
+        };
+        assert!(matches!(
+            torsion_sig.validate(),
+            Err(Error::InvalidSignature)
+        ));
+        assert!(!pk.verify(torsion_sig, b"hello"));
+    }
+
+    #[test]
+    fn test_uncompressed() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+        let pk = sk.public_key_min_sig();
+        let sig = sk.sign_min_sig(b"hello");
+
+        let raw = pk.to_uncompressed();
+        assert_eq!(raw.len(), 192);
+        assert_eq!(PublicKey::from_uncompressed(&raw).unwrap(), pk);
+        assert_eq!(PublicKey::from_uncompressed_unchecked(&raw).unwrap(), pk);
+        assert_eq!(PublicKey::from_compressed(&pk.to_compressed()).unwrap(), pk);
+
+        let raw = sig.to_uncompressed();
+        assert_eq!(raw.len(), 96);
+        assert_eq!(Signature::from_uncompressed(&raw).unwrap(), sig);
+        assert_eq!(Signature::from_uncompressed_unchecked(&raw).unwrap(), sig);
+        assert_eq!(
+            Signature::from_compressed(&sig.to_compressed()).unwrap(),
+            sig
+        );
+
+        // not on the curve
+        let mut invalid = raw;
+        invalid[95] ^= 1;
+        assert!(Signature::from_uncompressed(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+        let pk = sk.public_key_min_sig();
+        let sig = sk.sign_min_sig(b"hello");
+
+        // hex strings in human readable formats
+        let json = serde_json::to_string(&pk).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(pk.as_bytes())));
+        assert_eq!(serde_json::from_str::<PublicKey>(&json).unwrap(), pk);
+        let json = serde_json::to_string(&sig).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(sig.as_bytes())));
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), sig);
+
+        // raw bytes otherwise
+        let encoded = bincode::serialize(&pk).unwrap();
+        assert_eq!(&encoded[8..], &pk.as_bytes()[..]);
+        assert_eq!(bincode::deserialize::<PublicKey>(&encoded).unwrap(), pk);
+        let encoded = bincode::serialize(&sig.validate().unwrap()).unwrap();
+        assert_eq!(&encoded[8..], &sig.as_bytes()[..]);
+        assert_eq!(bincode::deserialize::<Signature>(&encoded).unwrap(), sig);
+
+        // decoding runs the same checks as `from_bytes`
+        let zero_key = PublicKey(G2Projective::identity());
+        let json = serde_json::to_string(&zero_key).unwrap();
+        assert!(serde_json::from_str::<PublicKey>(&json).is_ok());
+        assert!(serde_json::from_str::<ValidatedPublicKey>(&json).is_err());
+        assert!(serde_json::from_str::<ValidatedSignature>("\"zz\"").is_err());
+    }
+
+    #[test]
+    fn test_hash_vectors() {
+        // https://www.rfc-editor.org/rfc/rfc9380#appendix-J.9.1
+        const DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let cases: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1\
+                 08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+            ),
+            (
+                b"abc",
+                "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903\
+                 0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+            ),
+            (
+                b"abcdef0123456789",
+                "11e0b079dea29a68f0383ee94fed1b940995272407e3bb916bbf268c263ddd57a6a27200a784cbc248e84f357ce82d98\
+                 03a87ae2caf14e8ee52e51fa2ed8eefe80f02457004ba4d486d6aa1f517c0889501dc7413753f9599b099ebcbbd2d709",
+            ),
+        ];
+
+        for (msg, expected) in &cases {
+            let point = G1Affine::from(hash_to_g1(msg, DST));
+            assert_eq!(hex::encode(point.to_uncompressed()), *expected);
+        }
+    }
+
+    #[test]
+    fn test_sign_vectors() {
+        let mut raw =
+            hex::decode("0f0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+                .unwrap();
+        raw.reverse();
+        let sk = PrivateKey::from_bytes(&raw).unwrap();
+
+        let pk = sk.public_key_min_sig();
+        assert_eq!(
+            hex::encode(pk.as_bytes()),
+            "813f117c3ba2e77e6d5845e024669ef29ed7ea775b0fd521df333a33d3270564f6353d5e261eb419664689354e3b6d51\
+             0109e51a240d70a039f0b66438abda3380d8d76047ef8fa93662d7925b039669c6e85611030b0f7c80a74104c0813875"
+        );
+
+        let cases: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "80cc349a1ce8b35318d2d7ae521cf4b9ca602c48c2ef99214148db6a1cddc83ca2e0ded31af9128c6fc988e6ff4659b7",
+            ),
+            (
+                b"abc",
+                "ac251b049c62ca652961cdd8e831ed9d87ae236fd37112fb84418b1dedc6a489fce3ef5cec4dfbd37cab5d6dcc14d368",
+            ),
+            (
+                b"hello",
+                "ad6230738015eaec1a4fe3aa6fe5013bc0d180155f0673babdc5bccd2b430ef49ec96ea865e56ef6e73c5a1b1e55c6d4",
+            ),
+        ];
+
+        for (msg, expected) in &cases {
+            let signature = sk.sign_min_sig(msg);
+            assert_eq!(hex::encode(signature.as_bytes()), *expected);
+            assert!(pk.verify(Signature::from_bytes(&signature.as_bytes()).unwrap(), msg));
+        }
+    }
+
+    #[test]
+    fn test_blst_min_sig() {
+        // cross-check against the `min_sig` API of blst, which hashes on its own
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        for _ in 0..4 {
+            let sk = PrivateKey::generate(&mut rng);
+            let mut raw = sk.as_bytes();
+            raw.reverse();
+            let blst_sk = blst_lib::min_sig::SecretKey::from_bytes(&raw).unwrap();
+            let msg: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+
+            assert_eq!(
+                sk.public_key_min_sig().as_bytes(),
+                blst_sk.sk_to_pk().compress().to_vec()
+            );
+            assert_eq!(
+                sk.sign_min_sig(&msg).as_bytes(),
+                blst_sk.sign(&msg, CSUITE, &[]).compress().to_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn test_signature_traits() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+        let pk = sk.public_key_min_sig();
+        let msg = b"hello";
+
+        let sig: Signature = Signer::sign(&sk, msg);
+        assert_eq!(sig, sk.sign_min_sig(msg));
+        assert!(Verifier::verify(&pk, msg, &sig).is_ok());
+        assert!(Verifier::verify(&pk, b"other", &sig).is_err());
+
+        let bytes = sig.to_bytes();
+        assert_eq!(bytes, sig.to_compressed());
+        assert_eq!(sig.encoded_len(), 48);
+        assert_eq!(sig.to_vec(), sig.as_bytes());
+        assert_eq!(Signature::try_from(&bytes[..]).unwrap(), sig);
+        assert!(Signature::try_from(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+
+        let msg = (0..64).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let signature = sk.sign_min_sig(&msg);
+
+        let signature_bytes = signature.as_bytes();
+        assert_eq!(signature_bytes.len(), 48);
+        assert_eq!(Signature::from_bytes(&signature_bytes).unwrap(), signature);
+
+        let pk = sk.public_key_min_sig();
+        let pk_bytes = pk.as_bytes();
+
+        assert_eq!(pk_bytes.len(), 96);
+        assert_eq!(PublicKey::from_bytes(&pk_bytes).unwrap(), pk);
+
+        // sizes are swapped compared to the default variant
+        assert!(Signature::from_bytes(&pk_bytes).is_err());
+        assert!(PublicKey::from_bytes(&signature_bytes).is_err());
+    }
+}
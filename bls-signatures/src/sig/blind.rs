@@ -1,4 +1,4 @@
-use crate::group::{Element, Point, Scalar};
+use crate::group::{Element, Scalar};
 use crate::sig::bls::{common::BLSScheme, BLSError};
 use crate::sig::{BlindScheme, Scheme};
 use rand::RngCore;
@@ -58,11 +58,9 @@ where
             panic!("weak blinding because of broken RNG");
         }
 
-        let mut h = I::Signature::new();
-
         // r * H(m)
         // XXX result from zexe API but it shouldn't
-        h.map(msg).expect("could not map to the group");
+        let mut h = I::hash(msg).expect("could not map to the group");
         h.mul(&r);
 
         let serialized = bincode::serialize(&h).expect("serialization should not fail");
@@ -1,6 +1,7 @@
-use crate::group::{Element, PairingCurve, Point};
-use crate::sig::{Scheme, SignatureScheme};
-use std::{fmt::Debug, marker::PhantomData};
+use crate::group::{Element, HashMode, PairingCurve, Point, Standard};
+use crate::sig::{AggregateScheme, BatchVerifier, Scheme, SignatureScheme};
+use rand_core::RngCore;
+use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
 use thiserror::Error;
 
 /// BLSError are thrown out when using the BLS signature scheme.
@@ -16,6 +17,22 @@ pub enum BLSError {
 
     #[error("could not deserialize: {0}")]
     DeserializationError(#[from] bincode::Error),
+
+    /// Raised when aggregating or verifying without any signatures or public keys
+    #[error("no messages or public keys")]
+    Empty,
+
+    /// Raised when the number of messages and public keys differ
+    #[error("{0} messages but {1} public keys")]
+    LengthMismatch(usize, usize),
+
+    /// Raised when an aggregate signature covers the same message twice
+    #[error("messages {0} and {1} are equal")]
+    DuplicateMessage(usize, usize),
+
+    /// Raised when one of the public keys is the identity
+    #[error("public key {0} is the identity")]
+    IdentityPublicKey(usize),
 }
 
 // private module workaround to avoid leaking a private
@@ -28,6 +45,16 @@ pub mod common {
     /// BLSScheme is an internal trait that encompasses the common work between a
     /// BLS signature over G1 or G2.
     pub trait BLSScheme: Scheme {
+        /// How messages are hashed to the signature group.
+        type Hash: HashMode;
+
+        /// Maps the message to the signature group.
+        fn hash(msg: &[u8]) -> Result<Self::Signature, BLSError> {
+            let mut h = Self::Signature::new();
+            Self::Hash::map(&mut h, msg).map_err(|_| BLSError::HashingError)?;
+            Ok(h)
+        }
+
         /// Returns sig = msg^{private}. The message MUST be hashed before this call.
         fn internal_sign(
             private: &Self::Private,
@@ -35,9 +62,7 @@ pub mod common {
             should_hash: bool,
         ) -> Result<Vec<u8>, BLSError> {
             let mut h = if should_hash {
-                let mut h = Self::Signature::new();
-                h.map(msg).map_err(|_| BLSError::HashingError)?;
-                h
+                Self::hash(msg)?
             } else {
                 bincode::deserialize_from(msg)?
             };
@@ -57,9 +82,7 @@ pub mod common {
             let sig: Self::Signature = bincode::deserialize_from(sig_bytes)?;
 
             let h = if should_hash {
-                let mut h = Self::Signature::new();
-                h.map(msg).map_err(|_| BLSError::HashingError)?;
-                h
+                Self::hash(msg)?
             } else {
                 bincode::deserialize_from(msg)?
             };
@@ -73,7 +96,13 @@ pub mod common {
         }
 
         /// Performs the final exponentiation for the BLS sig scheme
-        fn final_exp(p: &Self::Public, sig: &Self::Signature, hm: &Self::Signature) -> bool;
+        fn final_exp(p: &Self::Public, sig: &Self::Signature, hm: &Self::Signature) -> bool {
+            Self::check_pairing(sig, &[(p.clone(), hm.clone())])
+        }
+
+        /// Checks that the signature pairs with the generator to the product of the
+        /// pairings of the (public key, hashed message) pairs, with a single multi-pairing.
+        fn check_pairing(sig: &Self::Signature, pairs: &[(Self::Public, Self::Signature)]) -> bool;
     }
 
     impl<T> SignatureScheme for T
@@ -94,64 +123,259 @@ pub mod common {
         ) -> Result<(), Self::Error> {
             T::internal_verify(public, msg_bytes, sig_bytes, true)
         }
+
+        fn sign_typed(private: &Self::Private, msg: &[u8]) -> Result<Self::Signature, Self::Error> {
+            let mut h = T::hash(msg)?;
+            h.mul(private);
+
+            Ok(h)
+        }
+
+        fn verify_typed(
+            public: &Self::Public,
+            msg: &[u8],
+            sig: &Self::Signature,
+        ) -> Result<(), Self::Error> {
+            let h = T::hash(msg)?;
+            if !T::final_exp(public, sig, &h) {
+                return Err(BLSError::InvalidSig);
+            }
+
+            Ok(())
+        }
+    }
+
+    impl<T> AggregateScheme for T
+    where
+        T: BLSScheme,
+    {
+        fn aggregate_sigs(sigs: &[Vec<u8>]) -> Result<Vec<u8>, BLSError> {
+            if sigs.is_empty() {
+                return Err(BLSError::Empty);
+            }
+
+            let mut aggregate = T::Signature::new();
+            for sig in sigs {
+                let sig: T::Signature = bincode::deserialize(sig)?;
+                aggregate.add(&sig);
+            }
+
+            Ok(bincode::serialize(&aggregate)?)
+        }
+
+        fn aggregate_verify(
+            publics: &[Self::Public],
+            msgs: &[&[u8]],
+            sig: &[u8],
+        ) -> Result<(), BLSError> {
+            check_publics::<T>(publics)?;
+            if msgs.len() != publics.len() {
+                return Err(BLSError::LengthMismatch(msgs.len(), publics.len()));
+            }
+
+            let mut seen = HashMap::with_capacity(msgs.len());
+            for (j, msg) in msgs.iter().enumerate() {
+                if let Some(i) = seen.insert(*msg, j) {
+                    return Err(BLSError::DuplicateMessage(i, j));
+                }
+            }
+
+            let sig = bincode::deserialize(sig)?;
+            let pairs = publics
+                .iter()
+                .zip(msgs)
+                .map(|(public, msg)| Ok((public.clone(), T::hash(msg)?)))
+                .collect::<Result<Vec<_>, BLSError>>()?;
+
+            if !T::check_pairing(&sig, &pairs) {
+                return Err(BLSError::InvalidSig);
+            }
+
+            Ok(())
+        }
+
+        /// Verifies the signature against the sum of the public keys.
+        fn fast_aggregate_verify(
+            publics: &[Self::Public],
+            msg: &[u8],
+            sig: &[u8],
+        ) -> Result<(), BLSError> {
+            check_publics::<T>(publics)?;
+
+            let mut public = T::Public::new();
+            for p in publics {
+                public.add(p);
+            }
+
+            T::internal_verify(&public, msg, sig, true)
+        }
+    }
+
+    impl<T> BatchVerifier for T
+    where
+        T: BLSScheme,
+    {
+        fn batch_verify<R: RngCore>(
+            batch: &[(Self::Public, &[u8], &[u8])],
+            rng: &mut R,
+        ) -> Result<(), Vec<usize>> {
+            let mut invalid = Vec::new();
+            let mut entries = Vec::with_capacity(batch.len());
+            for (i, (public, msg, sig)) in batch.iter().enumerate() {
+                match (bincode::deserialize(sig), T::hash(msg)) {
+                    (Ok(sig), Ok(hm)) => entries.push(BatchEntry {
+                        index: i,
+                        public,
+                        sig,
+                        hm,
+                    }),
+                    _ => invalid.push(i),
+                }
+            }
+
+            bisect::<T, R>(&entries, rng, &mut invalid);
+            if invalid.is_empty() {
+                return Ok(());
+            }
+
+            invalid.sort_unstable();
+            Err(invalid)
+        }
+    }
+
+    struct BatchEntry<'a, T: BLSScheme> {
+        index: usize,
+        public: &'a T::Public,
+        sig: T::Signature,
+        hm: T::Signature,
+    }
+
+    /// Checks the entries with one multi-pairing, weighting each with a fresh random
+    /// scalar so that invalid signatures can't cancel out. If the check fails, both
+    /// halves are checked separately until the invalid entries are found.
+    fn bisect<T: BLSScheme, R: RngCore>(
+        entries: &[BatchEntry<T>],
+        rng: &mut R,
+        invalid: &mut Vec<usize>,
+    ) {
+        if entries.is_empty() {
+            return;
+        }
+
+        // e(g, \sum r_i sig_i) == \prod e(r_i pub_i, H(m_i))
+        let mut sig = T::Signature::new();
+        let mut pairs = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let r = T::Private::rand(rng);
+
+            let mut weighted = entry.sig.clone();
+            weighted.mul(&r);
+            sig.add(&weighted);
+
+            let mut public = entry.public.clone();
+            public.mul(&r);
+            pairs.push((public, entry.hm.clone()));
+        }
+
+        if T::check_pairing(&sig, &pairs) {
+            return;
+        }
+
+        if entries.len() == 1 {
+            invalid.push(entries[0].index);
+            return;
+        }
+
+        let (left, right) = entries.split_at(entries.len() / 2);
+        bisect::<T, R>(left, rng, invalid);
+        bisect::<T, R>(right, rng, invalid);
+    }
+
+    fn check_publics<T: BLSScheme>(publics: &[T::Public]) -> Result<(), BLSError> {
+        if publics.is_empty() {
+            return Err(BLSError::Empty);
+        }
+
+        match publics.iter().position(|p| *p == T::Public::zero()) {
+            Some(i) => Err(BLSError::IdentityPublicKey(i)),
+            None => Ok(()),
+        }
     }
 }
 
 /// G1Scheme implements the BLS signature scheme with G1 as private / public
 /// keys and G2 as signature elements over the given pairing curve.
+/// Messages are hashed with `Standard` unless another `HashMode` is given.
 #[derive(Clone, Debug)]
-pub struct G1Scheme<C: PairingCurve> {
-    m: PhantomData<C>,
+pub struct G1Scheme<C: PairingCurve, H: HashMode = Standard> {
+    m: PhantomData<(C, H)>,
 }
 
-impl<C> Scheme for G1Scheme<C>
+impl<C, H> Scheme for G1Scheme<C, H>
 where
     C: PairingCurve,
+    H: HashMode,
 {
     type Private = C::Scalar;
     type Public = C::G1;
     type Signature = C::G2;
 }
 
-impl<C> common::BLSScheme for G1Scheme<C>
+impl<C, H> common::BLSScheme for G1Scheme<C, H>
 where
     C: PairingCurve,
+    H: HashMode,
 {
-    fn final_exp(p: &Self::Public, sig: &Self::Signature, hm: &Self::Signature) -> bool {
-        // e(g1,sig) == e(pub, H(m))
-        // e(g1,H(m))^x == e(g1,H(m))^x
-        let left = C::pair(&C::G1::one(), sig);
-        let right = C::pair(p, hm);
-        left == right
+    type Hash = H;
+
+    fn check_pairing(sig: &Self::Signature, pairs: &[(Self::Public, Self::Signature)]) -> bool {
+        // e(g1,sig) == \prod e(pub_i, H(m_i))
+        // checked as e(-g1,sig) * \prod e(pub_i, H(m_i)) == 1
+        let mut g1 = C::G1::one();
+        g1.negate();
+
+        let mut all = Vec::with_capacity(pairs.len() + 1);
+        all.push((g1, sig.clone()));
+        all.extend_from_slice(pairs);
+        C::pairing_check(&all)
     }
 }
 
 /// G2Scheme implements the BLS signature scheme with G2 as private / public
 /// keys and G1 as signature elements over the given pairing curve.
+/// Messages are hashed with `Standard` unless another `HashMode` is given.
 #[derive(Clone, Debug)]
-pub struct G2Scheme<C: PairingCurve> {
-    m: PhantomData<C>,
+pub struct G2Scheme<C: PairingCurve, H: HashMode = Standard> {
+    m: PhantomData<(C, H)>,
 }
 
-impl<C> Scheme for G2Scheme<C>
+impl<C, H> Scheme for G2Scheme<C, H>
 where
     C: PairingCurve,
+    H: HashMode,
 {
     type Private = C::Scalar;
     type Public = C::G2;
     type Signature = C::G1;
 }
 
-impl<C> common::BLSScheme for G2Scheme<C>
+impl<C, H> common::BLSScheme for G2Scheme<C, H>
 where
     C: PairingCurve,
+    H: HashMode,
 {
-    fn final_exp(p: &Self::Public, sig: &Self::Signature, hm: &Self::Signature) -> bool {
-        // e(sig,g2) == e(H(m),pub)
-        // e(H(m),g2)^x == e(H(m),g2)^x
-        let left = C::pair(sig, &Self::Public::one());
-        let right = C::pair(hm, p);
-        left == right
+    type Hash = H;
+
+    fn check_pairing(sig: &Self::Signature, pairs: &[(Self::Public, Self::Signature)]) -> bool {
+        // e(sig,g2) == \prod e(H(m_i),pub_i)
+        // checked as e(sig,-g2) * \prod e(H(m_i),pub_i) == 1
+        let mut g2 = C::G2::one();
+        g2.negate();
+
+        let mut all = Vec::with_capacity(pairs.len() + 1);
+        all.push((sig.clone(), g2));
+        all.extend(pairs.iter().map(|(p, hm)| (hm.clone(), p.clone())));
+        C::pairing_check(&all)
     }
 }
 
@@ -159,7 +383,8 @@ where
 mod tests {
     use super::*;
     use crate::curve::bls12377::{G1Curve, G2Curve, PairingCurve as PCurve};
-    use crate::group::Curve;
+    use crate::curve::bls12381;
+    use crate::group::{Curve, Legacy};
     use rand::prelude::*;
 
     fn keypair<C: Curve>() -> (C::Scalar, C::Point) {
@@ -169,6 +394,110 @@ mod tests {
         (private, public)
     }
 
+    fn aggregate_test<S: AggregateScheme<Error = BLSError>>() {
+        let msgs: Vec<&[u8]> = vec![b"one", b"two", b"three"];
+        let keys: Vec<_> = (0..msgs.len())
+            .map(|_| S::keypair(&mut thread_rng()))
+            .collect();
+        let publics: Vec<_> = keys.iter().map(|(_, public)| public.clone()).collect();
+
+        // distinct messages
+        let sigs: Vec<_> = keys
+            .iter()
+            .zip(&msgs)
+            .map(|((private, _), msg)| S::sign(private, msg).unwrap())
+            .collect();
+        let sig = S::aggregate_sigs(&sigs).unwrap();
+        S::aggregate_verify(&publics, &msgs, &sig).unwrap();
+        assert!(S::aggregate_verify(&publics[1..], &msgs[1..], &sig).is_err());
+        assert!(S::aggregate_verify(&publics, &[b"one", b"two", b"four"], &sig).is_err());
+        assert!(matches!(
+            S::aggregate_verify(&publics, &[b"one", b"two", b"one"], &sig),
+            Err(BLSError::DuplicateMessage(0, 2))
+        ));
+        assert!(matches!(
+            S::aggregate_verify(&publics[1..], &msgs, &sig),
+            Err(BLSError::LengthMismatch(3, 2))
+        ));
+        assert!(matches!(
+            S::aggregate_verify(&[], &[], &sig),
+            Err(BLSError::Empty)
+        ));
+        assert!(matches!(S::aggregate_sigs(&[]), Err(BLSError::Empty)));
+
+        // same message
+        let sigs: Vec<_> = keys
+            .iter()
+            .map(|(private, _)| S::sign(private, msgs[0]).unwrap())
+            .collect();
+        let sig = S::aggregate_sigs(&sigs).unwrap();
+        S::fast_aggregate_verify(&publics, msgs[0], &sig).unwrap();
+        assert!(S::fast_aggregate_verify(&publics, msgs[1], &sig).is_err());
+        assert!(S::fast_aggregate_verify(&publics[1..], msgs[0], &sig).is_err());
+
+        let mut with_zero = publics.clone();
+        with_zero.push(S::Public::zero());
+        assert!(matches!(
+            S::fast_aggregate_verify(&with_zero, msgs[0], &sig),
+            Err(BLSError::IdentityPublicKey(3))
+        ));
+    }
+
+    #[test]
+    fn aggregate_g1() {
+        aggregate_test::<G1Scheme<PCurve>>();
+        aggregate_test::<G1Scheme<bls12381::PairingCurve>>();
+    }
+
+    #[test]
+    fn aggregate_g2() {
+        aggregate_test::<G2Scheme<PCurve>>();
+        aggregate_test::<G2Scheme<bls12381::PairingCurve>>();
+    }
+
+    fn batch_test<S: BatchVerifier>() {
+        let rng = &mut thread_rng();
+        let msgs: Vec<Vec<u8>> = (0..9u8).map(|i| vec![i; 4]).collect();
+        let keys: Vec<_> = msgs.iter().map(|_| S::keypair(rng)).collect();
+        let mut sigs: Vec<_> = keys
+            .iter()
+            .zip(&msgs)
+            .map(|((private, _), msg)| S::sign(private, msg).unwrap())
+            .collect();
+
+        let publics: Vec<_> = keys.iter().map(|(_, public)| public.clone()).collect();
+        S::batch_verify(&batch::<S>(&publics, &msgs, &sigs), rng).unwrap();
+        S::batch_verify(&[], rng).unwrap();
+
+        // swapped signatures, and one that does not deserialize
+        sigs.swap(2, 7);
+        sigs[5] = vec![0; 3];
+        assert_eq!(
+            S::batch_verify(&batch::<S>(&publics, &msgs, &sigs), rng),
+            Err(vec![2, 5, 7])
+        );
+    }
+
+    fn batch<'a, S: Scheme>(
+        publics: &[S::Public],
+        msgs: &'a [Vec<u8>],
+        sigs: &'a [Vec<u8>],
+    ) -> Vec<(S::Public, &'a [u8], &'a [u8])> {
+        publics
+            .iter()
+            .zip(msgs)
+            .zip(sigs)
+            .map(|((public, msg), sig)| (public.clone(), &msg[..], &sig[..]))
+            .collect()
+    }
+
+    #[test]
+    fn batch_verify() {
+        batch_test::<G1Scheme<PCurve>>();
+        batch_test::<G2Scheme<PCurve>>();
+        batch_test::<G2Scheme<bls12381::PairingCurve>>();
+    }
+
     #[test]
     fn nbls_g2() {
         let (private, public) = keypair::<G2Curve>();
@@ -177,6 +506,23 @@ mod tests {
         G2Scheme::<PCurve>::verify(&public, &msg, &sig).expect("that should not happen");
     }
 
+    #[test]
+    fn hash_modes() {
+        type Standard381 = G1Scheme<bls12381::PairingCurve>;
+        type Legacy381 = G1Scheme<bls12381::PairingCurve, Legacy>;
+
+        let (private, public) = Standard381::keypair(&mut thread_rng());
+        let msg = vec![1, 9, 6, 9];
+
+        let sig = Standard381::sign(&private, &msg).unwrap();
+        Standard381::verify(&public, &msg, &sig).unwrap();
+        assert!(Legacy381::verify(&public, &msg, &sig).is_err());
+
+        let sig = Legacy381::sign(&private, &msg).unwrap();
+        Legacy381::verify(&public, &msg, &sig).unwrap();
+        assert!(Standard381::verify(&public, &msg, &sig).is_err());
+    }
+
     #[test]
     fn nbls_g1() {
         let (private, public) = keypair::<G1Curve>();
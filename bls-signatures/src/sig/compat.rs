@@ -0,0 +1,68 @@
+//! BLS over BLS12-381 with public keys in G1 and signatures in G2, hashing messages like
+//! [`sig_aggregate::hash`](../../sig_aggregate/fn.hash.html). Its (threshold) signatures
+//! convert to `sig_aggregate::Signature` and verify with `PublicKey::verify`.
+use crate::{curve::bls12381::PairingCurve, group::Standard, sig::G1Scheme};
+
+/// SigAggregateScheme is the BLS12-381 signature scheme of the `key` and `sig_aggregate`
+/// modules, over the arkworks types so that it can be used as a `ThresholdScheme`.
+pub type SigAggregateScheme = G1Scheme<PairingCurve, Standard>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        curve::bls12381::{Scalar, G1, G2},
+        key::{PrivateKey, PublicKey},
+        poly::{Idx, Poly},
+        sig::{Share, SignatureScheme, ThresholdScheme},
+        sig_aggregate::Signature,
+    };
+    use std::convert::TryFrom;
+
+    type S = SigAggregateScheme;
+
+    #[test]
+    fn same_signatures() {
+        let sk = PrivateKey::generate(&mut rand::thread_rng());
+        let msg = b"hello";
+
+        let sig = S::sign(&Scalar::from(&sk), msg).unwrap();
+        let sig: G2 = bincode::deserialize(&sig).unwrap();
+        assert_eq!(Signature::try_from(sig).unwrap(), sk.sign(msg));
+
+        let sig = bincode::serialize(&G2::from(sk.sign(msg))).unwrap();
+        S::verify(&G1::from(sk.public_key()), msg, &sig).unwrap();
+        assert!(S::verify(&G1::from(sk.public_key()), b"other", &sig).is_err());
+    }
+
+    #[test]
+    fn threshold_signatures() {
+        let (n, t) = (5, 3);
+        let private = Poly::<Scalar>::new(t - 1);
+        let public = private.commit::<G1>();
+        let msg = b"hello";
+
+        let partials: Vec<_> = (0..n)
+            .map(|i| {
+                let eval = private.eval(i as Idx);
+                let share = Share {
+                    index: eval.index,
+                    private: eval.value,
+                };
+                S::partial_sign(&share, msg).unwrap()
+            })
+            .collect();
+        for partial in &partials {
+            S::partial_verify(&public, msg, partial).unwrap();
+        }
+
+        let sig = S::aggregate(t, &partials[1..]).unwrap();
+        S::verify(public.public_key(), msg, &sig).unwrap();
+
+        let sig: G2 = bincode::deserialize(&sig).unwrap();
+        let sig = Signature::try_from(sig).unwrap();
+        let public_key = PublicKey::try_from(public.public_key().clone()).unwrap();
+        assert!(public_key.verify(sig, msg));
+        assert!(!public_key.verify(sig, b"other"));
+    }
+}
@@ -4,11 +4,14 @@ pub use blind::{BlindError, Token};
 mod bls;
 pub use bls::{BLSError, G1Scheme, G2Scheme};
 
+mod compat;
+pub use compat::SigAggregateScheme;
+
 mod tblind;
 pub use tblind::BlindThresholdError;
 
 mod tbls;
-pub use tbls::{Share, ThresholdError};
+pub use tbls::{PartialSignature, Share, ThresholdError};
 
 #[allow(clippy::module_inception)]
 mod sig;
@@ -1,4 +1,4 @@
-pub use super::tbls::Share; // import and re-export it for easier access
+pub use super::tbls::{PartialSignature, Share}; // import and re-export them for easier access
 use crate::{
     group::{Element, Point, Scalar},
     poly::Poly,
@@ -63,6 +63,54 @@ pub trait SignatureScheme: Scheme {
 
     /// Verifies that the signature on the provided message was produced by the public key
     fn verify(public: &Self::Public, msg: &[u8], sig: &[u8]) -> Result<(), Self::Error>;
+
+    /// Like `sign`, but returns the signature as a point instead of serializing it
+    fn sign_typed(private: &Self::Private, msg: &[u8]) -> Result<Self::Signature, Self::Error>;
+
+    /// Like `verify`, for a signature returned by `sign_typed`
+    fn verify_typed(
+        public: &Self::Public,
+        msg: &[u8],
+        sig: &Self::Signature,
+    ) -> Result<(), Self::Error>;
+}
+
+/// AggregateScheme is a signature scheme where signatures from different signers
+/// can be aggregated into one signature, which is verified against all of their
+/// public keys at once.
+pub trait AggregateScheme: SignatureScheme {
+    /// Aggregates signatures produced by `sign` into one signature
+    fn aggregate_sigs(sigs: &[Vec<u8>]) -> Result<Vec<u8>, Self::Error>;
+
+    /// Verifies an aggregate signature where `publics[i]` signed `msgs[i]`. The
+    /// messages must be distinct.
+    fn aggregate_verify(
+        publics: &[Self::Public],
+        msgs: &[&[u8]],
+        sig: &[u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Verifies an aggregate signature where all public keys signed the same message.
+    /// The public keys must come with a proof of possession, to prevent rogue key attacks.
+    fn fast_aggregate_verify(
+        publics: &[Self::Public],
+        msg: &[u8],
+        sig: &[u8],
+    ) -> Result<(), Self::Error>;
+}
+
+/// BatchVerifier checks many signatures, possibly from different signers on
+/// different messages, with a single multi-pairing instead of two pairings per
+/// signature.
+pub trait BatchVerifier: SignatureScheme {
+    /// Verifies all (public key, message, signature) triples at once, weighting
+    /// them with random scalars from the provided RNG. If some are invalid, the
+    /// batch is bisected to find them and their indices are returned. An empty
+    /// batch is valid.
+    fn batch_verify<R: RngCore>(
+        batch: &[(Self::Public, &[u8], &[u8])],
+        rng: &mut R,
+    ) -> Result<(), Vec<usize>>;
 }
 
 /*/// BlindScheme is a signature scheme where the message can be blinded before
@@ -158,6 +206,35 @@ pub trait ThresholdScheme: Scheme {
     /// not verify if the partial signatures are correct or not; it only
     /// aggregates them.
     fn aggregate(threshold: usize, partials: &[Partial]) -> Result<Vec<u8>, Self::Error>;
+
+    /// Aggregates the first `threshold` partial signatures which verify against the public
+    /// polynomial, skipping the ones which do not. Returns the signature along with the
+    /// positions in `partials` of the rejected partial signatures.
+    fn aggregate_verified(
+        public: &Poly<Self::Public>,
+        msg: &[u8],
+        threshold: usize,
+        partials: &[Partial],
+    ) -> Result<(Vec<u8>, Vec<usize>), Self::Error>;
+
+    /// Like `partial_sign`, but returns the partial signature as an index and a point
+    fn partial_sign_typed(
+        private: &Share<Self::Private>,
+        msg: &[u8],
+    ) -> Result<PartialSignature<Self::Signature>, Self::Error>;
+
+    /// Like `partial_verify`, for a partial signature returned by `partial_sign_typed`
+    fn partial_verify_typed(
+        public: &Poly<Self::Public>,
+        msg: &[u8],
+        partial: &PartialSignature<Self::Signature>,
+    ) -> Result<(), Self::Error>;
+
+    /// Like `aggregate`, for partial signatures returned by `partial_sign_typed`
+    fn aggregate_typed(
+        threshold: usize,
+        partials: &[PartialSignature<Self::Signature>],
+    ) -> Result<Self::Signature, Self::Error>;
 }
 
 /// BlindThreshold is ThresholdScheme that allows to verify a partially blinded
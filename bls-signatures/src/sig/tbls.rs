@@ -1,8 +1,10 @@
 //! Threshold Signatures implementation for any type which implements
 //! [`SignatureScheme`](../trait.SignatureScheme.html)
+use crate::group::Point;
 use crate::poly::{Eval, Idx, Poly, PolyError};
 use crate::sig::{Partial, SignatureScheme, ThresholdScheme};
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -14,6 +16,46 @@ pub struct Share<S> {
     pub private: S,
 }
 
+/// A partial signature, produced with the share of the given index
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PartialSignature<S> {
+    /// The index of the share which produced the signature
+    pub index: Idx,
+    /// The signature point
+    pub sig: S,
+}
+
+impl<S: Point> PartialSignature<S> {
+    /// Encodes the partial signature as the 4-byte little-endian index followed by the
+    /// compressed point.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.index.to_le_bytes().to_vec();
+        bincode::serialize_into(&mut bytes, &self.sig).expect("could not serialize");
+        bytes
+    }
+
+    /// Decodes a partial signature produced by `to_bytes`.
+    pub fn from_bytes(raw: &[u8]) -> Result<Self, bincode::Error> {
+        let invalid_length = || bincode::ErrorKind::Custom("invalid length".to_string()).into();
+        if raw.len() < IDX_SIZE {
+            return Err(invalid_length());
+        }
+
+        let (index, point) = raw.split_at(IDX_SIZE);
+        let sig = bincode::deserialize(point)?;
+        if bincode::serialized_size(&sig)? != point.len() as u64 {
+            return Err(invalid_length());
+        }
+
+        Ok(Self {
+            index: Idx::from_le_bytes(index.try_into().expect("slice has the size of an index")),
+            sig,
+        })
+    }
+}
+
+const IDX_SIZE: usize = std::mem::size_of::<Idx>();
+
 /// Errors associated with threshold signing, verification and aggregation.
 #[derive(Debug, Error)]
 pub enum ThresholdError<I: SignatureScheme> {
@@ -33,6 +75,46 @@ pub enum ThresholdError<I: SignatureScheme> {
     /// were fewer than the threshold
     #[error("not enough partial signatures: {0}/{1}")]
     NotEnoughPartialSignatures(usize, usize),
+
+    /// NotEnoughValidPartialSignatures is raised if fewer than the threshold of the
+    /// signatures provided for aggregation were valid, along with the positions of
+    /// the invalid ones
+    #[error("not enough valid partial signatures: {0}/{1}, invalid: {2:?}")]
+    NotEnoughValidPartialSignatures(usize, usize, Vec<usize>),
+
+    /// InvalidIndex is raised if a partial signature claims an index that no share can have
+    #[error("invalid partial signature index {0}")]
+    InvalidIndex(Idx),
+}
+
+/// Evaluates the public polynomial at the index of a partial signature. `Poly::eval` evaluates
+/// at `index + 1`, so the largest index is rejected instead of overflowing.
+fn public_share<I: SignatureScheme>(
+    public: &Poly<I::Public>,
+    index: Idx,
+) -> Result<I::Public, ThresholdError<I>> {
+    if index == Idx::MAX {
+        return Err(ThresholdError::InvalidIndex(index));
+    }
+
+    Ok(public.eval(index).value)
+}
+
+/// Deserializes and verifies a partial signature, returning its index and point.
+fn verify_partial<I: SignatureScheme>(
+    public: &Poly<I::Public>,
+    msg: &[u8],
+    partial: &[u8],
+) -> Result<Eval<I::Signature>, ThresholdError<I>> {
+    let eval: Eval<Vec<u8>> = bincode::deserialize(partial)?;
+    let sig = bincode::deserialize(&eval.value)?;
+    let public_i = public_share::<I>(public, eval.index)?;
+    I::verify_typed(&public_i, msg, &sig).map_err(ThresholdError::SignatureError)?;
+
+    Ok(Eval {
+        index: eval.index,
+        value: sig,
+    })
 }
 
 impl<I: SignatureScheme> ThresholdScheme for I {
@@ -58,9 +140,9 @@ impl<I: SignatureScheme> ThresholdScheme for I {
     ) -> Result<(), <Self as ThresholdScheme>::Error> {
         let partial: Eval<Vec<u8>> = bincode::deserialize(partial)?;
 
-        let public_i = public.eval(partial.index);
+        let public_i = public_share::<Self>(public, partial.index)?;
 
-        Self::verify(&public_i.value, msg, &partial.value).map_err(ThresholdError::SignatureError)
+        Self::verify(&public_i, msg, &partial.value).map_err(ThresholdError::SignatureError)
     }
 
     fn aggregate(
@@ -90,6 +172,100 @@ impl<I: SignatureScheme> ThresholdScheme for I {
             .map_err(ThresholdError::PolyError)?;
         Ok(bincode::serialize(&recovered_sig).expect("could not serialize"))
     }
+
+    fn aggregate_verified(
+        public: &Poly<Self::Public>,
+        msg: &[u8],
+        threshold: usize,
+        partials: &[Partial],
+    ) -> Result<(Vec<u8>, Vec<usize>), <Self as ThresholdScheme>::Error> {
+        if threshold > partials.len() {
+            return Err(ThresholdError::NotEnoughPartialSignatures(
+                partials.len(),
+                threshold,
+            ));
+        }
+
+        // verify the partials one by one, until there are enough valid ones
+        let mut valid: Vec<Eval<Self::Signature>> = Vec::with_capacity(threshold);
+        let mut invalid = Vec::new();
+        for (i, partial) in partials.iter().enumerate() {
+            if valid.len() == threshold {
+                break;
+            }
+
+            let eval = match verify_partial::<Self>(public, msg, partial) {
+                Ok(eval) => eval,
+                Err(_) => {
+                    invalid.push(i);
+                    continue;
+                }
+            };
+            // a valid signature from an index we already have is the same signature
+            if valid.iter().all(|e| e.index != eval.index) {
+                valid.push(eval);
+            }
+        }
+
+        if valid.len() < threshold {
+            return Err(ThresholdError::NotEnoughValidPartialSignatures(
+                valid.len(),
+                threshold,
+                invalid,
+            ));
+        }
+
+        let recovered_sig = Poly::<Self::Signature>::recover(threshold, valid)
+            .map_err(ThresholdError::PolyError)?;
+        Ok((
+            bincode::serialize(&recovered_sig).expect("could not serialize"),
+            invalid,
+        ))
+    }
+
+    fn partial_sign_typed(
+        private: &Share<Self::Private>,
+        msg: &[u8],
+    ) -> Result<PartialSignature<Self::Signature>, <Self as ThresholdScheme>::Error> {
+        let sig =
+            Self::sign_typed(&private.private, msg).map_err(ThresholdError::SignatureError)?;
+        Ok(PartialSignature {
+            index: private.index,
+            sig,
+        })
+    }
+
+    fn partial_verify_typed(
+        public: &Poly<Self::Public>,
+        msg: &[u8],
+        partial: &PartialSignature<Self::Signature>,
+    ) -> Result<(), <Self as ThresholdScheme>::Error> {
+        let public_i = public_share::<Self>(public, partial.index)?;
+
+        Self::verify_typed(&public_i, msg, &partial.sig).map_err(ThresholdError::SignatureError)
+    }
+
+    fn aggregate_typed(
+        threshold: usize,
+        partials: &[PartialSignature<Self::Signature>],
+    ) -> Result<Self::Signature, <Self as ThresholdScheme>::Error> {
+        if threshold > partials.len() {
+            return Err(ThresholdError::NotEnoughPartialSignatures(
+                partials.len(),
+                threshold,
+            ));
+        }
+
+        let evals = partials
+            .iter()
+            .map(|partial| Eval {
+                index: partial.index,
+                value: partial.sig.clone(),
+            })
+            .collect();
+
+        Poly::<Self::Signature>::recover(threshold, evals).map_err(ThresholdError::PolyError)
+    }
 }
 
 #[cfg(test)]
@@ -141,6 +317,139 @@ mod tests {
         T::verify(public.public_key(), &msg, &final_sig).unwrap();
     }
 
+    fn test_threshold_scheme_typed<T: ThresholdScheme + SignatureScheme>(creator: ShareCreator<T>) {
+        let threshold = 4;
+        let (shares, public) = creator(5, threshold);
+        let msg = vec![1, 9, 6, 9];
+
+        let partials: Vec<_> = shares
+            .iter()
+            .map(|s| T::partial_sign_typed(s, &msg).unwrap())
+            .collect();
+
+        for partial in &partials {
+            T::partial_verify_typed(&public, &msg, partial).unwrap();
+            assert!(T::partial_verify_typed(&public, &[1, 2], partial).is_err());
+
+            let bytes = partial.to_bytes();
+            let decoded = PartialSignature::<T::Signature>::from_bytes(&bytes).unwrap();
+            assert_eq!(&decoded, partial);
+            assert!(PartialSignature::<T::Signature>::from_bytes(&bytes[1..]).is_err());
+        }
+
+        assert!(T::aggregate_typed(threshold, &partials[..threshold - 1]).is_err());
+        let final_sig = T::aggregate_typed(threshold, &partials).unwrap();
+        T::verify_typed(public.public_key(), &msg, &final_sig).unwrap();
+
+        // the typed and byte APIs agree
+        let sig = T::aggregate(
+            threshold,
+            &shares
+                .iter()
+                .map(|s| T::partial_sign(s, &msg).unwrap())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(bincode::serialize(&final_sig).unwrap(), sig);
+    }
+
+    #[test]
+    fn threshold_typed() {
+        type S1 = G1Scheme<PCurve>;
+        test_threshold_scheme_typed::<S1>(shares::<S1>);
+        type S2 = G2Scheme<PCurve>;
+        test_threshold_scheme_typed::<S2>(shares::<S2>);
+    }
+
+    fn test_aggregate_verified<T: SignatureScheme>(creator: ShareCreator<T>) {
+        let threshold = 3;
+        let (shares, public) = creator(7, threshold);
+        let msg = vec![1, 9, 6, 9];
+
+        let mut partials: Vec<_> = shares
+            .iter()
+            .map(|s| T::partial_sign(s, &msg).unwrap())
+            .collect();
+        // a signature on another message, garbage, and a duplicate of a valid partial
+        partials[0] = T::partial_sign(&shares[0], &[1, 2]).unwrap();
+        partials[2] = vec![1, 2, 3];
+        partials[3] = partials[1].clone();
+
+        let (sig, invalid) = T::aggregate_verified(&public, &msg, threshold, &partials).unwrap();
+        assert_eq!(invalid, vec![0, 2]);
+        T::verify(public.public_key(), &msg, &sig).unwrap();
+        // the last partial was not needed
+        partials[6] = vec![];
+        let (other, _) = T::aggregate_verified(&public, &msg, threshold, &partials).unwrap();
+        assert_eq!(sig, other);
+
+        partials[4] = partials[0].clone();
+        match T::aggregate_verified(&public, &msg, threshold, &partials).unwrap_err() {
+            ThresholdError::NotEnoughValidPartialSignatures(valid, t, invalid) => {
+                assert_eq!((valid, t), (2, threshold));
+                assert_eq!(invalid, vec![0, 2, 4, 6]);
+            }
+            e => panic!("unexpected error {}", e),
+        }
+    }
+
+    #[test]
+    fn aggregate_verified() {
+        type S1 = G1Scheme<PCurve>;
+        test_aggregate_verified::<S1>(shares::<S1>);
+        type S2 = G2Scheme<PCurve>;
+        test_aggregate_verified::<S2>(shares::<S2>);
+    }
+
+    fn test_malicious_index<T: SignatureScheme>(creator: ShareCreator<T>) {
+        let threshold = 2;
+        let (shares, public) = creator(3, threshold);
+        let msg = vec![1, 9, 6, 9];
+        let is_invalid_index = |res| matches!(res, Err(ThresholdError::InvalidIndex(Idx::MAX)));
+
+        let mut typed = T::partial_sign_typed(&shares[0], &msg).unwrap();
+        typed.index = Idx::MAX;
+        assert!(is_invalid_index(T::partial_verify_typed(
+            &public, &msg, &typed
+        )));
+
+        let mut partials: Vec<_> = shares
+            .iter()
+            .map(|s| T::partial_sign(s, &msg).unwrap())
+            .collect();
+        let mut eval: Eval<Vec<u8>> = bincode::deserialize(&partials[0]).unwrap();
+        eval.index = Idx::MAX;
+        partials[0] = bincode::serialize(&eval).unwrap();
+        assert!(is_invalid_index(T::partial_verify(
+            &public,
+            &msg,
+            &partials[0]
+        )));
+
+        let (sig, invalid) = T::aggregate_verified(&public, &msg, threshold, &partials).unwrap();
+        assert_eq!(invalid, vec![0]);
+        T::verify(public.public_key(), &msg, &sig).unwrap();
+    }
+
+    #[test]
+    fn malicious_index() {
+        type S1 = G1Scheme<PCurve>;
+        test_malicious_index::<S1>(shares::<S1>);
+        type S2 = G2Scheme<PCurve>;
+        test_malicious_index::<S2>(shares::<S2>);
+    }
+
+    #[test]
+    fn partial_signature_size() {
+        type S = G1Scheme<PCurve>;
+        let (shares, _) = shares::<S>(2, 2);
+        let partial = S::partial_sign_typed(&shares[1], &[1, 9, 6, 9]).unwrap();
+        let bytes = partial.to_bytes();
+
+        assert_eq!(bytes.len(), 4 + 96);
+        assert_eq!(&bytes[..4], &partial.index.to_le_bytes());
+    }
+
     #[test]
     fn threshold_g1() {
         type S = G1Scheme<PCurve>;
@@ -1,5 +1,9 @@
-use std::io;
-use std::sync::atomic::{AtomicBool, Ordering};
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::{TryFrom, TryInto};
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(feature = "multicore")]
 use rayon::prelude::*;
@@ -7,27 +11,48 @@ use rayon::prelude::*;
 #[cfg(feature = "pairing")]
 use bls12_381::{
     hash_to_curve::{ExpandMsgXmd, HashToCurve},
-    Bls12, G1Affine, G2Affine, G2Projective, Gt, MillerLoopResult,
+    Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, MillerLoopResult,
+    Scalar,
 };
+use ff::Field;
 use pairing_lib::MultiMillerLoop;
-
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use signature::SignatureEncoding;
 
 #[cfg(feature = "blst")]
-use blstrs::{Bls12, G1Affine, G2Affine, G2Projective, Gt, MillerLoopResult};
+use blstrs::{
+    Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, MillerLoopResult, Scalar,
+};
 #[cfg(feature = "blst")]
 use group::{prime::PrimeCurveAffine, Group};
 #[cfg(feature = "blst")]
 use pairing_lib::MillerLoopResult as _;
 
-use crate::error::Error;
-use crate::key::*;
+use crate::error::{Error, VerificationError};
+use crate::key::{impl_serde, *};
 
 const CSUITE: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
-const G2_COMPRESSED_SIZE: usize = 96;
+const AUG_CSUITE: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_";
+const POP_CSUITE: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+const POP_SIG_CSUITE: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+pub(crate) const G2_COMPRESSED_SIZE: usize = 96;
+pub(crate) const G2_UNCOMPRESSED_SIZE: usize = 192;
+
+/// Tags longer than this are hashed down, see
+/// https://www.rfc-editor.org/rfc/rfc9380#section-5.3.3
+const MAX_DST_SIZE: usize = 255;
+const OVERSIZE_DST_SALT: &[u8] = b"H2C-OVERSIZE-DST-";
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Signature(G2Affine);
 
+/// Proof that the holder of a public key knows the matching private key.
+/// See Section 3.3 of the IRTF's BLS signatures spec:
+/// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-3.3
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ProofOfPossession(G2Affine);
+
 impl From<G2Projective> for Signature {
     fn from(val: G2Projective) -> Self {
         Signature(val.into())
@@ -52,38 +77,214 @@ impl From<Signature> for G2Affine {
 }
 
 impl Serialize for Signature {
-    fn write_bytes(&self, dest: &mut impl io::Write) -> io::Result<()> {
-        dest.write_all(&self.0.to_compressed())?;
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        let g2 = g2_from_slice(raw)?;
+        Ok(g2.into())
+    }
 
-        Ok(())
+    fn as_bytes(&self) -> Vec<u8> {
+        self.to_compressed().to_vec()
+    }
+}
+
+impl Signature {
+    /// Encodes the signature with point compression, as `as_bytes` does.
+    pub fn to_compressed(&self) -> [u8; G2_COMPRESSED_SIZE] {
+        self.0.to_compressed()
+    }
+
+    /// Decodes a signature produced by `to_compressed`, checking that it is on the curve and in
+    /// the prime-order subgroup.
+    pub fn from_compressed(raw: &[u8; G2_COMPRESSED_SIZE]) -> Result<Self, Error> {
+        Option::from(G2Affine::from_compressed(raw))
+            .map(Signature)
+            .ok_or(Error::GroupDecode)
+    }
+
+    /// Encodes the signature without point compression, which is faster to decode.
+    pub fn to_uncompressed(&self) -> [u8; G2_UNCOMPRESSED_SIZE] {
+        self.0.to_uncompressed()
+    }
+
+    /// Decodes a signature produced by `to_uncompressed`, checking that it is on the curve and
+    /// in the prime-order subgroup.
+    pub fn from_uncompressed(raw: &[u8; G2_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        Option::from(G2Affine::from_uncompressed(raw))
+            .map(Signature)
+            .ok_or(Error::GroupDecode)
+    }
+
+    /// Decodes a signature produced by `to_uncompressed` without any checks, only for trusted
+    /// sources such as a local cache.
+    pub fn from_uncompressed_unchecked(raw: &[u8; G2_UNCOMPRESSED_SIZE]) -> Result<Self, Error> {
+        Option::from(G2Affine::from_uncompressed_unchecked(raw))
+            .map(Signature)
+            .ok_or(Error::GroupDecode)
+    }
+
+    /// Rejects signatures outside of the prime-order subgroup.
+    /// See Section 2.8 of the IRTF's BLS signatures spec:
+    /// https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04#section-2.8
+    pub fn validate(&self) -> Result<ValidatedSignature, Error> {
+        if !bool::from(self.0.is_on_curve()) || !bool::from(self.0.is_torsion_free()) {
+            return Err(Error::InvalidSignature);
+        }
+
+        Ok(ValidatedSignature(*self))
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = Error;
+
+    fn try_from(raw: &[u8]) -> Result<Self, Error> {
+        Signature::from_bytes(raw)
+    }
+}
+
+impl From<Signature> for [u8; G2_COMPRESSED_SIZE] {
+    fn from(val: Signature) -> Self {
+        val.to_compressed()
+    }
+}
+
+impl SignatureEncoding for Signature {
+    type Repr = [u8; G2_COMPRESSED_SIZE];
+}
+
+/// A signature that passed the subgroup check, as required by all verification functions.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ValidatedSignature(Signature);
+
+impl ValidatedSignature {
+    /// Skips the subgroup check, only for signatures from a trusted source.
+    pub fn new_unchecked(signature: Signature) -> Self {
+        ValidatedSignature(signature)
+    }
+}
+
+impl Deref for ValidatedSignature {
+    type Target = Signature;
+
+    fn deref(&self) -> &Signature {
+        &self.0
+    }
+}
+
+impl From<ValidatedSignature> for Signature {
+    fn from(val: ValidatedSignature) -> Self {
+        val.0
+    }
+}
+
+impl TryFrom<Signature> for ValidatedSignature {
+    type Error = Error;
+
+    fn try_from(val: Signature) -> Result<Self, Error> {
+        val.validate()
+    }
+}
+
+impl Serialize for ValidatedSignature {
+    fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        Signature::from_bytes(raw)?.validate()
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes()
+    }
+}
+
+impl_serde!(Signature);
+impl_serde!(ValidatedSignature);
+
+impl From<G2Projective> for ProofOfPossession {
+    fn from(val: G2Projective) -> Self {
+        ProofOfPossession(val.into())
+    }
+}
+
+impl From<G2Affine> for ProofOfPossession {
+    fn from(val: G2Affine) -> Self {
+        ProofOfPossession(val)
+    }
+}
+
+impl From<ProofOfPossession> for G2Affine {
+    fn from(val: ProofOfPossession) -> Self {
+        val.0
     }
+}
 
+impl Serialize for ProofOfPossession {
     fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
         let g2 = g2_from_slice(raw)?;
         Ok(g2.into())
     }
-}
 
-fn g2_from_slice(raw: &[u8]) -> Result<G2Affine, Error> {
-    if raw.len() != G2_COMPRESSED_SIZE {
-        return Err(Error::SizeMismatch);
+    fn as_bytes(&self) -> Vec<u8> {
+        self.0.to_compressed().to_vec()
     }
+}
 
-    let mut res = [0u8; G2_COMPRESSED_SIZE];
-    res.copy_from_slice(raw);
+pub(crate) fn g2_from_slice(raw: &[u8]) -> Result<G2Affine, Error> {
+    let raw: &[u8; G2_COMPRESSED_SIZE] = raw.try_into().map_err(|_| Error::SizeMismatch)?;
 
-    Option::from(G2Affine::from_compressed(&res)).ok_or(Error::GroupDecode)
+    Option::from(G2Affine::from_compressed(raw)).ok_or(Error::GroupDecode)
 }
 
 /// Hash the given message, as used in the signature.
-#[cfg(feature = "pairing")]
 pub fn hash(msg: &[u8]) -> G2Projective {
-    <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, CSUITE)
+    hash_to_g2(msg, CSUITE)
+}
+
+/// Hash the given message under the given domain separation tag.
+/// Tags longer than 255 bytes are reduced as specified by RFC 9380.
+pub fn hash_with_dst(msg: &[u8], dst: &[u8]) -> Result<G2Projective, Error> {
+    let dst = validate_dst(dst)?;
+
+    Ok(hash_to_g2(msg, &dst))
+}
+
+fn validate_dst(dst: &[u8]) -> Result<Cow<'_, [u8]>, Error> {
+    if dst.is_empty() {
+        return Err(Error::InvalidDst);
+    }
+
+    if dst.len() > MAX_DST_SIZE {
+        let reduced = Sha256::new().chain(OVERSIZE_DST_SALT).chain(dst).finalize();
+        return Ok(Cow::Owned(reduced.to_vec()));
+    }
+
+    Ok(Cow::Borrowed(dst))
+}
+
+/// Hash the given message prefixed by the signer's public key, as used in augmented signatures.
+pub fn hash_augmented(public_key: &PublicKey, msg: &[u8]) -> G2Projective {
+    let mut augmented = public_key.as_bytes();
+    augmented.extend_from_slice(msg);
+
+    hash_to_g2(&augmented, AUG_CSUITE)
+}
+
+/// Hash the given message, as used in signatures of the IRTF's "POP" scheme.
+pub fn hash_pop_scheme(msg: &[u8]) -> G2Projective {
+    hash_to_g2(msg, POP_SIG_CSUITE)
+}
+
+/// Hash the given public key, as used in the proof of possession.
+pub(crate) fn pop_hash(public_key: &PublicKey) -> G2Projective {
+    hash_to_g2(&public_key.as_bytes(), POP_CSUITE)
+}
+
+#[cfg(feature = "pairing")]
+fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Projective {
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, dst)
 }
 
 #[cfg(feature = "blst")]
-pub fn hash(msg: &[u8]) -> G2Projective {
-    G2Projective::hash_to_curve(msg, CSUITE, &[])
+fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Projective {
+    G2Projective::hash_to_curve(msg, dst, &[])
 }
 
 /// Aggregate signatures by multiplying them together.
@@ -122,35 +323,187 @@ pub fn aggregate(signatures: &[Signature]) -> Result<Signature, Error> {
     Ok(Signature(res.into()))
 }
 
+/// Running aggregate of signatures together with the aggregate of the matching public keys.
+/// Both stay in projective form until `finalize` is called, so signatures can be added and
+/// removed one at a time, and partial aggregates from different sources can be merged.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AggregateSignature {
+    signature: G2Projective,
+    public_key: G1Projective,
+    count: usize,
+}
+
+impl Default for AggregateSignature {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AggregateSignature {
+    /// Creates an empty aggregate.
+    pub fn new() -> Self {
+        AggregateSignature {
+            signature: G2Projective::identity(),
+            public_key: G1Projective::identity(),
+            count: 0,
+        }
+    }
+
+    /// Adds a signature and the public key that produced it.
+    pub fn add(&mut self, signature: &Signature, public_key: &PublicKey) {
+        self.signature += &signature.0;
+        self.public_key += &public_key.0;
+        self.count += 1;
+    }
+
+    /// Removes a signature and public key that were previously added.
+    ///
+    /// Only the number of signatures is tracked, not which ones were added, so the caller must
+    /// guarantee that this exact pair was added before. Removing any other pair is only caught
+    /// when the aggregate is empty, otherwise the aggregate silently stops verifying.
+    pub fn subtract(&mut self, signature: &Signature, public_key: &PublicKey) -> Result<(), Error> {
+        if self.count == 0 {
+            return Err(Error::ZeroSizedInput);
+        }
+
+        self.signature -= &signature.0;
+        self.public_key -= &public_key.0;
+        self.count -= 1;
+
+        Ok(())
+    }
+
+    /// Adds all signatures and public keys of another aggregate.
+    pub fn merge(&mut self, other: &AggregateSignature) {
+        self.signature += &other.signature;
+        self.public_key += &other.public_key;
+        self.count += other.count;
+    }
+
+    /// Returns the number of signatures in the aggregate.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the sum of the public keys of all signers, for use when they all signed the
+    /// same message.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.public_key)
+    }
+
+    /// Returns the aggregated signature.
+    pub fn finalize(&self) -> Result<Signature, Error> {
+        if self.count == 0 {
+            return Err(Error::ZeroSizedInput);
+        }
+
+        Ok(self.signature.into())
+    }
+}
+
 /// Verifies that the signature is the actual aggregated signature of hashes - pubkeys.
 /// Calculated by `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)`.
-pub fn verify(signature: &Signature, hashes: &[G2Projective], public_keys: &[PublicKey]) -> bool {
-    if hashes.is_empty() || public_keys.is_empty() {
-        return false;
+pub fn verify(
+    signature: &ValidatedSignature,
+    hashes: &[G2Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> bool {
+    try_verify(signature, hashes, public_keys).is_ok()
+}
+
+/// Like `verify`, but reports why verification failed.
+pub fn try_verify(
+    signature: &ValidatedSignature,
+    hashes: &[G2Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), Error> {
+    check_hashes(hashes, public_keys)?;
+
+    if !verify_pairing(signature, hashes, public_keys) {
+        return Err(VerificationError::InvalidSignature.into());
     }
 
-    let n_hashes = hashes.len();
+    Ok(())
+}
 
-    if n_hashes != public_keys.len() {
-        return false;
+/// Checks that there is one public key per message, and that none of them is the identity.
+fn check_public_keys(
+    n_messages: usize,
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), VerificationError> {
+    if n_messages == 0 || public_keys.is_empty() {
+        return Err(VerificationError::Empty);
     }
 
-    // zero key & single hash should fail
-    if n_hashes == 1 && public_keys[0].0.is_identity().into() {
-        return false;
+    if n_messages != public_keys.len() {
+        return Err(VerificationError::LengthMismatch(
+            n_messages,
+            public_keys.len(),
+        ));
     }
 
+    match public_keys
+        .iter()
+        .position(|pk| bool::from(pk.0.is_identity()))
+    {
+        Some(i) => Err(VerificationError::IdentityPublicKey(i)),
+        None => Ok(()),
+    }
+}
+
+/// Checks the shape of a `verify` input and that all hashes are distinct.
+fn check_hashes(
+    hashes: &[G2Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), VerificationError> {
+    check_public_keys(hashes.len(), public_keys)?;
+
     // Enforce that messages are distinct as a countermeasure against BLS's rogue-key attack.
     // See Section 3.1. of the IRTF's BLS signatures spec:
     // https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.1
+    let n_hashes = hashes.len();
     for i in 0..(n_hashes - 1) {
         for j in (i + 1)..n_hashes {
             if hashes[i] == hashes[j] {
-                return false;
+                return Err(VerificationError::DuplicateMessage(i, j));
             }
         }
     }
 
+    Ok(())
+}
+
+/// Checks the shape of a `verify_messages` input and that all messages are distinct.
+fn check_messages(
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), VerificationError> {
+    check_public_keys(messages.len(), public_keys)?;
+
+    // Enforce that messages are distinct as a countermeasure against BLS's rogue-key attack.
+    // See Section 3.1. of the IRTF's BLS signatures spec:
+    // https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.1
+    let mut seen = BTreeMap::new();
+    for (j, msg) in messages.iter().enumerate() {
+        if let Some(i) = seen.insert(*msg, j) {
+            return Err(VerificationError::DuplicateMessage(i, j));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)` without any checks on the hashes.
+/// Callers must ensure `hashes` and `public_keys` are non-empty and of equal length.
+fn verify_pairing(
+    signature: &Signature,
+    hashes: &[G2Projective],
+    public_keys: &[ValidatedPublicKey],
+) -> bool {
     let is_valid = AtomicBool::new(true);
 
     #[cfg(feature = "multicore")]
@@ -192,56 +545,228 @@ pub fn verify(signature: &Signature, hashes: &[G2Projective], public_keys: &[Pub
     ml.final_exponentiation() == Gt::identity()
 }
 
-/// Verifies that the signature is the actual aggregated signature of messages - pubkeys.
-/// Calculated by `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)`.
-#[cfg(feature = "pairing")]
-pub fn verify_messages(
-    signature: &Signature,
+/// Verifies that the signature is the aggregated augmented signature of messages - pubkeys.
+/// Calculated by `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_aug(pk_i || message_i))`.
+///
+/// Messages do not need to be distinct, as each one is bound to its signer's public key.
+pub fn aggregate_verify_augmented(
+    signature: &ValidatedSignature,
     messages: &[&[u8]],
-    public_keys: &[PublicKey],
+    public_keys: &[ValidatedPublicKey],
 ) -> bool {
+    try_aggregate_verify_augmented(signature, messages, public_keys).is_ok()
+}
+
+/// Like `aggregate_verify_augmented`, but reports why verification failed.
+pub fn try_aggregate_verify_augmented(
+    signature: &ValidatedSignature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), Error> {
+    check_public_keys(messages.len(), public_keys)?;
+
     #[cfg(feature = "multicore")]
-    let hashes: Vec<_> = messages.par_iter().map(|msg| hash(msg)).collect();
+    let hashes: Vec<_> = messages
+        .par_iter()
+        .zip(public_keys.par_iter())
+        .map(|(msg, pk)| hash_augmented(pk, msg))
+        .collect();
 
     #[cfg(not(feature = "multicore"))]
-    let hashes: Vec<_> = messages.iter().map(|msg| hash(msg)).collect();
+    let hashes: Vec<_> = messages
+        .iter()
+        .zip(public_keys.iter())
+        .map(|(msg, pk)| hash_augmented(pk, msg))
+        .collect();
+
+    if !verify_pairing(signature, &hashes, public_keys) {
+        return Err(VerificationError::InvalidSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Verifies that the signature is the aggregated "POP" scheme signature of a single
+/// message - pubkeys, see `PrivateKey::sign_pop`.
+/// Calculated by `e(g1, signature) == e(\sum_{i = 0}^n pk_i, hash_pop_scheme(message))`.
+///
+/// Only secure if every public key was checked with `verify_pop` beforehand.
+pub fn fast_aggregate_verify(
+    signature: &ValidatedSignature,
+    message: &[u8],
+    public_keys: &[ValidatedPublicKey],
+) -> bool {
+    try_fast_aggregate_verify(signature, message, public_keys).is_ok()
+}
+
+/// Like `fast_aggregate_verify`, but reports why verification failed.
+pub fn try_fast_aggregate_verify(
+    signature: &ValidatedSignature,
+    message: &[u8],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), Error> {
+    if public_keys.is_empty() {
+        return Err(VerificationError::Empty.into());
+    }
+
+    if let Some(i) = public_keys
+        .iter()
+        .position(|pk| bool::from(pk.0.is_identity()))
+    {
+        return Err(VerificationError::IdentityPublicKey(i).into());
+    }
+
+    #[cfg(feature = "multicore")]
+    let aggregated_key = public_keys
+        .par_iter()
+        .fold(G1Projective::identity, |mut acc, pk| {
+            acc += &pk.0;
+            acc
+        })
+        .reduce(G1Projective::identity, |acc, val| acc + val);
+
+    #[cfg(not(feature = "multicore"))]
+    let aggregated_key = public_keys
+        .iter()
+        .fold(G1Projective::identity(), |acc, pk| acc + pk.0);
+
+    let pk = G1Affine::from(aggregated_key);
+    let h = G2Affine::from(hash_pop_scheme(message)).into();
+    let g1_neg = -G1Affine::generator();
 
-    verify(signature, &hashes, public_keys)
+    let sig = G2Affine::from(signature.0).into();
+
+    let ml = Bls12::multi_miller_loop(&[(&pk, &h), (&g1_neg, &sig)]);
+
+    if ml.final_exponentiation() != Gt::identity() {
+        return Err(VerificationError::InvalidSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Verifies a proof of possession for the given public key.
+/// Calculated by `e(g1, proof) == e(pk, hash_pop(pk))`.
+pub fn verify_pop(proof: &ProofOfPossession, public_key: &PublicKey) -> bool {
+    try_verify_pop(proof, public_key).is_ok()
+}
+
+/// Like `verify_pop`, but reports why verification failed.
+pub fn try_verify_pop(proof: &ProofOfPossession, public_key: &PublicKey) -> Result<(), Error> {
+    public_key.validate()?;
+
+    let pk = public_key.as_affine();
+    let h = G2Affine::from(pop_hash(public_key)).into();
+    let g1_neg = -G1Affine::generator();
+
+    let ml = Bls12::multi_miller_loop(&[(&pk, &h), (&g1_neg, &proof.0.into())]);
+
+    if ml.final_exponentiation() != Gt::identity() {
+        return Err(VerificationError::InvalidSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Verifies that the signature is the actual aggregated signature of messages - pubkeys,
+/// where every message was signed under the given domain separation tag.
+/// Calculated by `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_dst(message_i))`.
+pub fn verify_messages_with_dst(
+    signature: &ValidatedSignature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+    dst: &[u8],
+) -> bool {
+    try_verify_messages_with_dst(signature, messages, public_keys, dst).is_ok()
+}
+
+/// Like `verify_messages_with_dst`, but reports why verification failed, including
+/// `Error::InvalidDst` for an empty tag.
+pub fn try_verify_messages_with_dst(
+    signature: &ValidatedSignature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+    dst: &[u8],
+) -> Result<(), Error> {
+    let dst = validate_dst(dst)?;
+    check_messages(messages, public_keys)?;
+
+    #[cfg(feature = "multicore")]
+    let hashes: Vec<_> = messages
+        .par_iter()
+        .map(|msg| hash_to_g2(msg, &dst))
+        .collect();
+
+    #[cfg(not(feature = "multicore"))]
+    let hashes: Vec<_> = messages.iter().map(|msg| hash_to_g2(msg, &dst)).collect();
+
+    if !verify_pairing(signature, &hashes, public_keys) {
+        return Err(VerificationError::InvalidSignature.into());
+    }
+
+    Ok(())
 }
 
 /// Verifies that the signature is the actual aggregated signature of messages - pubkeys.
 /// Calculated by `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)`.
-#[cfg(all(feature = "blst", feature = "multicore"))]
 pub fn verify_messages(
-    signature: &Signature,
+    signature: &ValidatedSignature,
     messages: &[&[u8]],
-    public_keys: &[PublicKey],
+    public_keys: &[ValidatedPublicKey],
 ) -> bool {
-    if messages.is_empty() || public_keys.is_empty() {
-        return false;
-    }
+    try_verify_messages(signature, messages, public_keys).is_ok()
+}
 
-    let n_messages = messages.len();
+/// Like `verify_messages`, but reports why verification failed.
+#[cfg(feature = "pairing")]
+pub fn try_verify_messages(
+    signature: &ValidatedSignature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), Error> {
+    check_messages(messages, public_keys)?;
 
-    if n_messages != public_keys.len() {
-        return false;
-    }
+    #[cfg(feature = "multicore")]
+    let hashes: Vec<_> = messages.par_iter().map(|msg| hash(msg)).collect();
 
-    // zero key & single message should fail
-    if n_messages == 1 && public_keys[0].0.is_identity().into() {
-        return false;
+    #[cfg(not(feature = "multicore"))]
+    let hashes: Vec<_> = messages.iter().map(|msg| hash(msg)).collect();
+
+    if !verify_pairing(signature, &hashes, public_keys) {
+        return Err(VerificationError::InvalidSignature.into());
     }
 
-    // Enforce that messages are distinct as a countermeasure against BLS's rogue-key attack.
-    // See Section 3.1. of the IRTF's BLS signatures spec:
-    // https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.1
-    if !blstrs::unique_messages(messages) {
-        return false;
+    Ok(())
+}
+
+/// Like `verify_messages`, but reports why verification failed.
+#[cfg(feature = "blst")]
+pub fn try_verify_messages(
+    signature: &ValidatedSignature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> Result<(), Error> {
+    check_messages(messages, public_keys)?;
+
+    if !verify_messages_blst(signature, messages, public_keys) {
+        return Err(VerificationError::InvalidSignature.into());
     }
 
+    Ok(())
+}
+
+/// Checks `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)` using blst's pairing
+/// aggregation, assuming `check_messages` already passed.
+#[cfg(all(feature = "blst", feature = "multicore"))]
+fn verify_messages_blst(
+    signature: &Signature,
+    messages: &[&[u8]],
+    public_keys: &[ValidatedPublicKey],
+) -> bool {
+    let n_messages = messages.len();
     let valid = AtomicBool::new(true);
 
-    let n_workers = std::cmp::min(rayon::current_num_threads(), n_messages);
+    let n_workers = core::cmp::min(rayon::current_num_threads(), n_messages);
     let mut pairings = messages
         .par_iter()
         .zip(public_keys.par_iter())
@@ -280,36 +805,14 @@ pub fn verify_messages(
     valid.load(Ordering::Relaxed) && acc.finalverify(Some(&gtsig))
 }
 
-/// Verifies that the signature is the actual aggregated signature of messages - pubkeys.
-/// Calculated by `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)`.
+/// Checks `e(g1, signature) == \prod_{i = 0}^n e(pk_i, hash_i)` using blst's pairing
+/// aggregation, assuming `check_messages` already passed.
 #[cfg(all(feature = "blst", not(feature = "multicore")))]
-pub fn verify_messages(
+fn verify_messages_blst(
     signature: &Signature,
     messages: &[&[u8]],
-    public_keys: &[PublicKey],
+    public_keys: &[ValidatedPublicKey],
 ) -> bool {
-    if messages.is_empty() || public_keys.is_empty() {
-        return false;
-    }
-
-    let n_messages = messages.len();
-
-    if n_messages != public_keys.len() {
-        return false;
-    }
-
-    // zero key & single message should fail
-    if n_messages == 1 && public_keys[0].0.is_identity().into() {
-        return false;
-    }
-
-    // Enforce that messages are distinct as a countermeasure against BLS's rogue-key attack.
-    // See Section 3.1. of the IRTF's BLS signatures spec:
-    // https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-02#section-3.1
-    if !blstrs::unique_messages(messages) {
-        return false;
-    }
-
     let mut valid = true;
     let mut pairing = blstrs::PairingG1G2::new(true, CSUITE);
     for (message, public_key) in messages.iter().zip(public_keys.iter()) {
@@ -330,6 +833,226 @@ pub fn verify_messages(
     valid && pairing.finalverify(Some(&gtsig))
 }
 
+/// Verifies many independent aggregate signatures at once.
+///
+/// Each entry is weighted by a fresh random scalar, so that all of them can be checked with a
+/// single multi-Miller loop and final exponentiation:
+/// `e(g1, \sum_j r_j * signature_j) == \prod_j \prod_i e(r_j * pk_ji, hash_ji)`.
+#[derive(Debug, Clone)]
+pub struct BatchVerifier {
+    entries: Vec<BatchEntry>,
+    scalar_bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+struct BatchEntry {
+    signature: ValidatedSignature,
+    hashes: Vec<G2Projective>,
+    public_keys: Vec<ValidatedPublicKey>,
+}
+
+impl Default for BatchVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchVerifier {
+    /// Creates an empty batch using 128-bit random scalars.
+    pub fn new() -> Self {
+        BatchVerifier {
+            entries: Vec::new(),
+            scalar_bytes: 16,
+        }
+    }
+
+    /// Creates an empty batch using 64-bit random scalars. This is faster, but a batch
+    /// containing an invalid signature is accepted with probability up to 2^-64.
+    pub fn new_64() -> Self {
+        BatchVerifier {
+            entries: Vec::new(),
+            scalar_bytes: 8,
+        }
+    }
+
+    /// Adds an aggregate signature over the given messages - pubkeys.
+    pub fn add(
+        &mut self,
+        signature: ValidatedSignature,
+        messages: &[&[u8]],
+        public_keys: &[ValidatedPublicKey],
+    ) {
+        #[cfg(feature = "multicore")]
+        let hashes: Vec<_> = messages.par_iter().map(|msg| hash(msg)).collect();
+
+        #[cfg(not(feature = "multicore"))]
+        let hashes: Vec<_> = messages.iter().map(|msg| hash(msg)).collect();
+
+        self.add_hashes(signature, hashes, public_keys);
+    }
+
+    /// Adds an aggregate signature over the given hashes - pubkeys.
+    pub fn add_hashes(
+        &mut self,
+        signature: ValidatedSignature,
+        hashes: Vec<G2Projective>,
+        public_keys: &[ValidatedPublicKey],
+    ) {
+        self.entries.push(BatchEntry {
+            signature,
+            hashes,
+            public_keys: public_keys.to_vec(),
+        });
+    }
+
+    /// Returns the number of signatures in the batch.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Verifies all signatures in the batch. An empty batch is trivially valid.
+    pub fn verify<R: RngCore + CryptoRng>(&self, rng: &mut R) -> bool {
+        if self.entries.is_empty() {
+            return true;
+        }
+
+        let valid_inputs = self
+            .entries
+            .iter()
+            .all(|entry| check_hashes(&entry.hashes, &entry.public_keys).is_ok());
+        if !valid_inputs {
+            return false;
+        }
+
+        let scalars: Vec<Scalar> = self
+            .entries
+            .iter()
+            .map(|_| random_scalar(rng, self.scalar_bytes))
+            .collect();
+
+        #[cfg(feature = "multicore")]
+        let (signature, mut terms) = {
+            let signature = self
+                .entries
+                .par_iter()
+                .zip(scalars.par_iter())
+                .map(|(entry, r)| G2Projective::from(entry.signature.0) * r)
+                .reduce(G2Projective::identity, |acc, val| acc + val);
+            let terms = self
+                .entries
+                .par_iter()
+                .zip(scalars.par_iter())
+                .flat_map_iter(|(entry, r)| entry.terms(r))
+                .collect::<Vec<_>>();
+            (signature, terms)
+        };
+
+        #[cfg(not(feature = "multicore"))]
+        let (signature, mut terms) = {
+            let signature = self
+                .entries
+                .iter()
+                .zip(scalars.iter())
+                .fold(G2Projective::identity(), |acc, (entry, r)| {
+                    acc + G2Projective::from(entry.signature.0) * r
+                });
+            let terms = self
+                .entries
+                .iter()
+                .zip(scalars.iter())
+                .flat_map(|(entry, r)| entry.terms(r))
+                .collect::<Vec<_>>();
+            (signature, terms)
+        };
+
+        terms.push((
+            -G1Affine::generator(),
+            G2Prepared::from(G2Affine::from(signature)),
+        ));
+
+        let terms = terms.iter().map(|(p, q)| (p, q)).collect::<Vec<_>>();
+        let ml = Bls12::multi_miller_loop(&terms);
+
+        ml.final_exponentiation() == Gt::identity()
+    }
+
+    /// Verifies all signatures in the batch. If the batch does not verify, each signature is
+    /// checked on its own and the indices of the invalid ones are returned, in insertion order.
+    /// An empty batch has no invalid signatures, and returns `Ok(())`.
+    pub fn verify_or_find_invalid<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(), Vec<usize>> {
+        if self.verify(rng) {
+            return Ok(());
+        }
+
+        #[cfg(feature = "multicore")]
+        let invalid: Vec<usize> = self
+            .entries
+            .par_iter()
+            .enumerate()
+            .filter(|(_, entry)| !verify(&entry.signature, &entry.hashes, &entry.public_keys))
+            .map(|(i, _)| i)
+            .collect();
+
+        #[cfg(not(feature = "multicore"))]
+        let invalid: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !verify(&entry.signature, &entry.hashes, &entry.public_keys))
+            .map(|(i, _)| i)
+            .collect();
+
+        if invalid.is_empty() {
+            return Ok(());
+        }
+
+        Err(invalid)
+    }
+}
+
+impl BatchEntry {
+    /// Returns the weighted pairing terms `(r * pk_i, hash_i)` of this entry.
+    fn terms<'a>(&'a self, r: &'a Scalar) -> impl Iterator<Item = (G1Affine, G2Prepared)> + 'a {
+        self.public_keys
+            .iter()
+            .zip(self.hashes.iter())
+            .map(move |(pk, h)| {
+                let pk = G1Affine::from(pk.0 * r);
+                let h = G2Prepared::from(G2Affine::from(h));
+                (pk, h)
+            })
+    }
+}
+
+/// Samples a non-zero scalar of at most `n_bytes` random bytes.
+fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R, n_bytes: usize) -> Scalar {
+    loop {
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes[..n_bytes]);
+
+        let r = scalar_from_u128_bytes(&bytes);
+        if !bool::from(r.is_zero()) {
+            return r;
+        }
+    }
+}
+
+/// Interprets 16 little-endian bytes as a scalar.
+pub(crate) fn scalar_from_u128_bytes(bytes: &[u8; 16]) -> Scalar {
+    let lo = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+    let hi = u64::from_le_bytes(bytes[8..].try_into().unwrap());
+    let shift = Scalar::from(1u64 << 32).square();
+
+    Scalar::from(hi) * shift + Scalar::from(lo)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -341,12 +1064,6 @@ mod tests {
 
     #[cfg(feature = "pairing")]
     use crate::key::G1_COMPRESSED_SIZE;
-    #[cfg(feature = "pairing")]
-    use bls12_381::{G1Projective, Scalar};
-    #[cfg(feature = "blst")]
-    use blstrs::{G1Projective, Scalar};
-    #[cfg(feature = "blst")]
-    use ff::Field;
 
     #[test]
     fn basic_aggregation() {
@@ -371,7 +1088,10 @@ mod tests {
             .map(|(message, pk)| pk.sign(message))
             .collect::<Vec<Signature>>();
 
-        let aggregated_signature = aggregate(&sigs).expect("failed to aggregate");
+        let aggregated_signature = aggregate(&sigs)
+            .expect("failed to aggregate")
+            .validate()
+            .unwrap();
 
         let hashes = messages
             .iter()
@@ -379,7 +1099,7 @@ mod tests {
             .collect::<Vec<_>>();
         let public_keys = private_keys
             .iter()
-            .map(|pk| pk.public_key())
+            .map(|pk| pk.public_key().validate().unwrap())
             .collect::<Vec<_>>();
 
         assert!(
@@ -415,13 +1135,16 @@ mod tests {
             .map(|pk| pk.sign(&message))
             .collect::<Vec<Signature>>();
 
-        let aggregated_signature = aggregate(&sigs).expect("failed to aggregate");
+        let aggregated_signature = aggregate(&sigs)
+            .expect("failed to aggregate")
+            .validate()
+            .unwrap();
 
         // check that equal messages can not be aggreagated
         let hashes: Vec<_> = (0..num_messages).map(|_| hash(&message)).collect();
         let public_keys = private_keys
             .iter()
-            .map(|pk| pk.public_key())
+            .map(|pk| pk.public_key().validate().unwrap())
             .collect::<Vec<_>>();
         assert!(
             !verify(&aggregated_signature, &hashes, &public_keys),
@@ -436,50 +1159,643 @@ mod tests {
         ));
     }
 
+    /// Secret key, public key and the signatures of 32 repetitions of a byte.
+    type PopSchemeVector = (&'static str, &'static str, [(u8, &'static str); 3]);
+
+    /// Signing vectors of the Ethereum consensus spec tests, which use the "POP" scheme.
+    /// Secret keys are big-endian there.
+    const POP_SCHEME_VECTORS: [PopSchemeVector; 3] = [
+        (
+            "263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3",
+            "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a",
+            [
+                (0x00, "b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55"),
+                (0x56, "882730e5d03f6b42c3abc26d3372625034e1d871b65a8a6b900a56dae22da98abbe1b68f85e49fe7652a55ec3d0591c20767677e33e5cbb1207315c41a9ac03be39c2e7668edc043d6cb1d9fd93033caa8a1c5b0e84bedaeb6c64972503a43eb"),
+                (0xab, "91347bccf740d859038fcdcaf233eeceb2a436bcaaee9b2aa3bfb70efe29dfb2677562ccbea1c8e061fb9971b0753c240622fab78489ce96768259fc01360346da5b9f579e5da0d941e4c6ba18a0e64906082375394f337fa1af2b7127b0d121"),
+            ],
+        ),
+        (
+            "47b8192d77bf871b62e87859d653922725724a5c031afeabc60bcef5ff665138",
+            "b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81",
+            [
+                (0x00, "b23c46be3a001c63ca711f87a005c200cc550b9429d5f4eb38d74322144f1b63926da3388979e5321012fb1a0526bcd100b5ef5fe72628ce4cd5e904aeaa3279527843fae5ca9ca675f4f51ed8f83bbf7155da9ecc9663100a885d5dc6df96d9"),
+                (0x56, "af1390c3c47acdb37131a51216da683c509fce0e954328a59f93aebda7e4ff974ba208d9a4a2a2389f892a9d418d618418dd7f7a6bc7aa0da999a9d3a5b815bc085e14fd001f6a1948768a3f4afefc8b8240dda329f984cb345c6363272ba4fe"),
+                (0xab, "9674e2228034527f4c083206032b020310face156d4a4685e2fcaec2f6f3665aa635d90347b6ce124eb879266b1e801d185de36a0a289b85e9039662634f2eea1e02e670bc7ab849d006a70b2f93b84597558a05b879c8d445f387a5d5b653df"),
+            ],
+        ),
+        (
+            "328388aff0d4a5b7dc9205abd374e7e98f3cd9f3418edb4eafda5fb16473d216",
+            "b53d21a4cfd562c469cc81514d4ce5a6b577d8403d32a394dc265dd190b47fa9f829fdd7963afdf972e5e77854051f6f",
+            [
+                (0x00, "948a7cb99f76d616c2c564ce9bf4a519f1bea6b0a624a02276443c245854219fabb8d4ce061d255af5330b078d5380681751aa7053da2c98bae898edc218c75f07e24d8802a17cd1f6833b71e58f5eb5b94208b4d0bb3848cecb075ea21be115"),
+                (0x56, "a4efa926610b8bd1c8330c918b7a5e9bf374e53435ef8b7ec186abf62e1b1f65aeaaeb365677ac1d1172a1f5b44b4e6d022c252c58486c0a759fbdc7de15a756acc4d343064035667a594b4c2a6f0b0b421975977f297dba63ee2f63ffe47bb6"),
+                (0xab, "ae82747ddeefe4fd64cf9cedb9b04ae3e8a43420cd255e3c7cd06a8d88b7c7f8638543719981c5d16fa3527c468c25f0026704a6951bde891360c7e8d12ddee0559004ccdbe6046b55bae1b257ee97f7cdb955773d7cf29adf3ccbb9975e4eb9"),
+            ],
+        ),
+    ];
+
+    fn pop_scheme_key(secret: &str) -> PrivateKey {
+        let mut raw = hex::decode(secret).unwrap();
+        raw.reverse();
+
+        PrivateKey::from_bytes(&raw).unwrap()
+    }
+
     #[test]
-    fn test_zero_key() {
+    fn pop_scheme_vectors() {
+        for (secret, public, sigs) in POP_SCHEME_VECTORS.iter() {
+            let sk = pop_scheme_key(secret);
+            let pk = sk.public_key();
+            assert_eq!(hex::encode(pk.as_bytes()), *public);
+
+            for (byte, expected) in sigs.iter() {
+                let msg = [*byte; 32];
+                let sig = sk.sign_pop(msg);
+                assert_eq!(hex::encode(sig.as_bytes()), *expected);
+
+                assert!(pk.verify_pop_scheme(sig, msg));
+                assert!(!pk.verify_pop_scheme(sig, [!*byte; 32]));
+                // the "POP" and "NUL" schemes hash under different tags
+                assert!(!pk.verify(sig, msg));
+                assert_ne!(sig, sk.sign(msg));
+            }
+        }
+    }
+
+    #[test]
+    fn fast_aggregation_same_message() {
         let mut rng = ChaCha8Rng::seed_from_u64(12);
 
-        // In the current iteration we expect the zero key to be valid and work.
-        let zero_key: PrivateKey = Scalar::zero().into();
-        assert!(bool::from(zero_key.public_key().0.is_identity()));
+        let num_keys = 10;
+
+        // generate private keys
+        let private_keys: Vec<_> = (0..num_keys)
+            .map(|_| PrivateKey::generate(&mut rng))
+            .collect();
+
+        // generate message
+        let message: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+
+        // sign message
+        let sigs = private_keys
+            .iter()
+            .map(|pk| pk.sign_pop(&message))
+            .collect::<Vec<Signature>>();
+
+        let aggregated_signature = aggregate(&sigs)
+            .expect("failed to aggregate")
+            .validate()
+            .unwrap();
+
+        let public_keys = private_keys
+            .iter()
+            .map(|pk| pk.public_key().validate().unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(fast_aggregate_verify(
+            &aggregated_signature,
+            &message,
+            &public_keys
+        ));
+
+        // wrong message
+        assert!(!fast_aggregate_verify(
+            &aggregated_signature,
+            b"other message",
+            &public_keys
+        ));
+
+        // missing signer
+        assert!(!fast_aggregate_verify(
+            &aggregated_signature,
+            &message,
+            &public_keys[1..]
+        ));
+
+        // no signers
+        assert!(!fast_aggregate_verify(&aggregated_signature, &message, &[]));
+
+        // zero key is rejected even though it does not change the sum
+        let zero_key = PublicKey(G1Projective::identity());
+        let mut with_zero = public_keys.clone();
+        with_zero.push(ValidatedPublicKey::new_unchecked(zero_key));
+        assert!(!fast_aggregate_verify(
+            &aggregated_signature,
+            &message,
+            &with_zero
+        ));
+    }
+
+    #[test]
+    fn fast_aggregation_vectors() {
+        let public_keys: Vec<_> = POP_SCHEME_VECTORS
+            .iter()
+            .map(|(secret, _, _)| pop_scheme_key(secret).public_key().validate().unwrap())
+            .collect();
+        let sigs: Vec<_> = POP_SCHEME_VECTORS
+            .iter()
+            .map(|(_, _, sigs)| Signature::from_bytes(&hex::decode(sigs[2].1).unwrap()).unwrap())
+            .collect();
+        let aggregated_signature = aggregate(&sigs).unwrap().validate().unwrap();
+        let message = [0xab; 32];
+
+        assert!(fast_aggregate_verify(
+            &aggregated_signature,
+            &message,
+            &public_keys
+        ));
+        assert!(!fast_aggregate_verify(
+            &aggregated_signature,
+            &[0x56; 32],
+            &public_keys
+        ));
+        assert!(!fast_aggregate_verify(
+            &aggregated_signature,
+            &message,
+            &public_keys[1..]
+        ));
+    }
+
+    #[test]
+    fn augmented_aggregation_same_messages() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
 
-        println!(
-            "{:?}\n{:?}",
-            zero_key.public_key().as_bytes(),
-            zero_key.as_bytes()
-        );
         let num_messages = 10;
 
         // generate private keys
-        let mut private_keys: Vec<_> = (0..num_messages - 1)
+        let private_keys: Vec<_> = (0..num_messages)
             .map(|_| PrivateKey::generate(&mut rng))
             .collect();
 
-        private_keys.push(zero_key);
+        // generate messages
+        let message: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+
+        // sign messages
+        let sigs = private_keys
+            .iter()
+            .map(|pk| pk.sign_augmented(&message))
+            .collect::<Vec<Signature>>();
+
+        let aggregated_signature = aggregate(&sigs)
+            .expect("failed to aggregate")
+            .validate()
+            .unwrap();
+
+        let public_keys = private_keys
+            .iter()
+            .map(|pk| pk.public_key().validate().unwrap())
+            .collect::<Vec<_>>();
+        let messages = vec![&message[..]; num_messages];
+
+        assert!(aggregate_verify_augmented(
+            &aggregated_signature,
+            &messages[..],
+            &public_keys
+        ));
+
+        // augmented signatures are not valid under the basic scheme and vice versa
+        assert!(!verify_messages(
+            &aggregated_signature,
+            &messages[..],
+            &public_keys
+        ));
+        let sig = private_keys[0].sign(&message);
+        assert!(!public_keys[0].verify_augmented(sig, &message));
+        assert!(public_keys[0].verify_augmented(sigs[0], &message));
+
+        // signer mismatch
+        let mut swapped = public_keys.clone();
+        swapped.swap(0, 1);
+        let mut other_messages = messages.clone();
+        other_messages[1] = b"other message";
+        assert!(!aggregate_verify_augmented(
+            &aggregated_signature,
+            &other_messages[..],
+            &public_keys
+        ));
+        assert!(!aggregate_verify_augmented(
+            &sigs[0].validate().unwrap(),
+            &messages[..1],
+            &swapped[..1]
+        ));
+    }
+
+    #[test]
+    fn custom_dst() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let dst_a: &[u8] = b"PROTOCOL_A_BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+        let dst_b: &[u8] = b"PROTOCOL_B_BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+        let private_keys: Vec<_> = (0..3).map(|_| PrivateKey::generate(&mut rng)).collect();
+        let public_keys: Vec<_> = private_keys
+            .iter()
+            .map(|sk| sk.public_key().validate().unwrap())
+            .collect();
+        let messages: Vec<Vec<u8>> = (0..3)
+            .map(|_| (0..64).map(|_| rng.gen()).collect())
+            .collect();
+        let messages = messages.iter().map(|r| &r[..]).collect::<Vec<_>>();
+
+        let sigs = messages
+            .iter()
+            .zip(&private_keys)
+            .map(|(message, sk)| sk.sign_with_dst(message, dst_a).unwrap())
+            .collect::<Vec<_>>();
+        let aggregated_signature = aggregate(&sigs)
+            .expect("failed to aggregate")
+            .validate()
+            .unwrap();
+
+        assert!(public_keys[0].verify_with_dst(sigs[0], messages[0], dst_a));
+        assert!(verify_messages_with_dst(
+            &aggregated_signature,
+            &messages,
+            &public_keys,
+            dst_a
+        ));
+
+        let hashes = messages
+            .iter()
+            .map(|msg| hash_with_dst(msg, dst_a).unwrap())
+            .collect::<Vec<_>>();
+        assert!(verify(&aggregated_signature, &hashes, &public_keys));
+
+        // signatures do not carry over to another tag
+        assert!(!public_keys[0].verify_with_dst(sigs[0], messages[0], dst_b));
+        assert!(!public_keys[0].verify(sigs[0], messages[0]));
+        assert!(!verify_messages_with_dst(
+            &aggregated_signature,
+            &messages,
+            &public_keys,
+            dst_b
+        ));
+
+        // the default tag is just another tag
+        assert_eq!(
+            private_keys[0].sign_with_dst(messages[0], CSUITE).unwrap(),
+            private_keys[0].sign(messages[0])
+        );
+
+        // empty tags are rejected
+        assert!(hash_with_dst(messages[0], b"").is_err());
+        assert!(private_keys[0].sign_with_dst(messages[0], b"").is_err());
+        assert!(!public_keys[0].verify_with_dst(sigs[0], messages[0], b""));
+    }
+
+    #[test]
+    fn long_dst() {
+        let long_dst = [b'a'; 256];
+        let reduced = Sha256::new()
+            .chain(b"H2C-OVERSIZE-DST-")
+            .chain(&long_dst[..])
+            .finalize();
+
+        let msg = b"hello";
+        assert_eq!(
+            hash_with_dst(msg, &long_dst).unwrap(),
+            hash_with_dst(msg, &reduced).unwrap()
+        );
+
+        // a tag at the limit is used as is
+        assert_ne!(
+            hash_with_dst(msg, &long_dst[..255]).unwrap(),
+            hash_with_dst(msg, &reduced).unwrap()
+        );
+
+        let sk = PrivateKey::new([7u8; 32]);
+        let sig = sk.sign_with_dst(msg, &long_dst).unwrap();
+        assert!(sk.public_key().verify_with_dst(sig, msg, &long_dst));
+    }
+
+    fn batch_entry(
+        rng: &mut ChaCha8Rng,
+        n: usize,
+    ) -> (ValidatedSignature, Vec<Vec<u8>>, Vec<ValidatedPublicKey>) {
+        let private_keys: Vec<_> = (0..n).map(|_| PrivateKey::generate(rng)).collect();
+        let messages: Vec<Vec<u8>> = (0..n)
+            .map(|_| (0..64).map(|_| rng.gen()).collect())
+            .collect();
+        let sigs = messages
+            .iter()
+            .zip(&private_keys)
+            .map(|(message, pk)| pk.sign(message))
+            .collect::<Vec<Signature>>();
+        let public_keys = private_keys
+            .iter()
+            .map(|pk| pk.public_key().validate().unwrap())
+            .collect();
+
+        (
+            aggregate(&sigs).unwrap().validate().unwrap(),
+            messages,
+            public_keys,
+        )
+    }
+
+    #[test]
+    fn batch_verification() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let entries: Vec<_> = (1..6).map(|n| batch_entry(&mut rng, n)).collect();
+
+        let mut batch = BatchVerifier::new();
+        let mut batch_64 = BatchVerifier::new_64();
+        assert!(batch.verify(&mut rng));
+        assert_eq!(batch.verify_or_find_invalid(&mut rng), Ok(()));
+
+        for (sig, messages, public_keys) in &entries {
+            let messages = messages.iter().map(|r| &r[..]).collect::<Vec<_>>();
+            batch.add(*sig, &messages, public_keys);
+            batch_64.add(*sig, &messages, public_keys);
+        }
+        assert_eq!(batch.len(), 5);
+        assert!(batch.verify(&mut rng));
+        assert!(batch_64.verify(&mut rng));
+        assert_eq!(batch.verify_or_find_invalid(&mut rng), Ok(()));
+
+        // swap the signatures of two entries, which keeps their sum unchanged
+        let mut batch = BatchVerifier::new();
+        for (i, (_, messages, public_keys)) in entries.iter().enumerate() {
+            let sig = match i {
+                1 => entries[3].0,
+                3 => entries[1].0,
+                _ => entries[i].0,
+            };
+            let messages = messages.iter().map(|r| &r[..]).collect::<Vec<_>>();
+            batch.add(sig, &messages, public_keys);
+        }
+        assert!(!batch.verify(&mut rng));
+        assert_eq!(batch.verify_or_find_invalid(&mut rng), Err(vec![1, 3]));
+
+        // malformed entries are reported as well
+        let (sig, messages, public_keys) = &entries[2];
+        let messages = messages.iter().map(|r| &r[..]).collect::<Vec<_>>();
+        let mut batch = BatchVerifier::new();
+        batch.add(*sig, &messages, public_keys);
+        batch.add(*sig, &messages[..2], public_keys);
+        batch.add(*sig, &[messages[0], messages[0], messages[0]], public_keys);
+        assert_eq!(batch.verify_or_find_invalid(&mut rng), Err(vec![1, 2]));
+    }
+
+    #[test]
+    fn streaming_aggregation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let num_keys = 10;
+
+        // generate private keys
+        let private_keys: Vec<_> = (0..num_keys)
+            .map(|_| PrivateKey::generate(&mut rng))
+            .collect();
+
+        // generate message
+        let message: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+
+        // sign message
+        let sigs = private_keys
+            .iter()
+            .map(|pk| pk.sign_pop(&message))
+            .collect::<Vec<Signature>>();
+        let public_keys = private_keys
+            .iter()
+            .map(|pk| pk.public_key().validate().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut acc = AggregateSignature::new();
+        assert!(acc.finalize().is_err());
+        assert!(acc.subtract(&sigs[0], &public_keys[0]).is_err());
+
+        for (sig, pk) in sigs.iter().zip(&public_keys) {
+            acc.add(sig, pk);
+        }
+        assert_eq!(acc.len(), num_keys);
+        assert_eq!(acc.finalize().unwrap(), aggregate(&sigs).unwrap());
+        assert!(acc
+            .public_key()
+            .verify_pop_scheme(acc.finalize().unwrap(), &message));
+
+        // removing a signer
+        acc.subtract(&sigs[0], &public_keys[0]).unwrap();
+        assert_eq!(acc.len(), num_keys - 1);
+        assert_eq!(acc.finalize().unwrap(), aggregate(&sigs[1..]).unwrap());
+        assert!(fast_aggregate_verify(
+            &acc.finalize().unwrap().validate().unwrap(),
+            &message,
+            &public_keys[1..]
+        ));
+
+        // merging partial aggregates
+        let mut left = AggregateSignature::new();
+        let mut right = AggregateSignature::new();
+        for i in 0..num_keys {
+            if i < 4 {
+                left.add(&sigs[i], &public_keys[i]);
+            } else {
+                right.add(&sigs[i], &public_keys[i]);
+            }
+        }
+        left.merge(&right);
+        assert_eq!(left.len(), num_keys);
+        assert_eq!(left.finalize().unwrap(), aggregate(&sigs).unwrap());
+        assert_eq!(
+            left.public_key(),
+            PublicKey(acc.public_key().0 + public_keys[0].0)
+        );
+    }
+
+    #[test]
+    fn verification_errors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        let private_keys: Vec<_> = (0..3).map(|_| PrivateKey::generate(&mut rng)).collect();
+        let public_keys: Vec<_> = private_keys
+            .iter()
+            .map(|sk| sk.public_key().validate().unwrap())
+            .collect();
+        let messages: Vec<&[u8]> = vec![b"zero", b"one", b"two"];
+        let sigs: Vec<_> = private_keys
+            .iter()
+            .zip(messages.iter())
+            .map(|(sk, msg)| sk.sign(msg))
+            .collect();
+        let signature = aggregate(&sigs).unwrap().validate().unwrap();
+
+        assert!(try_verify_messages(&signature, &messages, &public_keys).is_ok());
+
+        let err = |res: Result<(), Error>| match res {
+            Err(Error::Verification(e)) => e,
+            res => panic!("unexpected result {:?}", res),
+        };
+
+        assert_eq!(
+            err(try_verify_messages(&signature, &[], &[])),
+            VerificationError::Empty
+        );
+        assert_eq!(
+            err(try_verify_messages(
+                &signature,
+                &messages,
+                &public_keys[..2]
+            )),
+            VerificationError::LengthMismatch(3, 2)
+        );
+        assert_eq!(
+            err(try_verify_messages(
+                &signature,
+                &[b"zero", b"one", b"zero"],
+                &public_keys
+            )),
+            VerificationError::DuplicateMessage(0, 2)
+        );
+        let mut zero_keys = public_keys.clone();
+        zero_keys[1] = ValidatedPublicKey::new_unchecked(PublicKey(G1Projective::identity()));
+        assert_eq!(
+            err(try_verify_messages(&signature, &messages, &zero_keys)),
+            VerificationError::IdentityPublicKey(1)
+        );
+        assert_eq!(
+            err(try_verify_messages(
+                &sigs[0].validate().unwrap(),
+                &messages,
+                &public_keys
+            )),
+            VerificationError::InvalidSignature
+        );
+
+        // the hash based variant reports the same errors
+        let hashes: Vec<_> = messages.iter().map(|msg| hash(msg)).collect();
+        assert!(try_verify(&signature, &hashes, &public_keys).is_ok());
+        assert_eq!(
+            err(try_verify(
+                &signature,
+                &[hashes[0], hashes[1], hashes[1]],
+                &public_keys
+            )),
+            VerificationError::DuplicateMessage(1, 2)
+        );
+        assert_eq!(
+            err(try_verify(
+                &sigs[0].validate().unwrap(),
+                &hashes,
+                &public_keys
+            )),
+            VerificationError::InvalidSignature
+        );
+
+        assert!(public_keys[0].try_verify(sigs[0], messages[0]).is_ok());
+        assert_eq!(
+            err(public_keys[0].try_verify(sigs[0], messages[1])),
+            VerificationError::InvalidSignature
+        );
+        // as do the augmented, same message and tagged variants
+        assert_eq!(
+            err(try_aggregate_verify_augmented(
+                &signature,
+                &messages,
+                &public_keys[1..]
+            )),
+            VerificationError::LengthMismatch(3, 2)
+        );
+        assert_eq!(
+            err(try_aggregate_verify_augmented(
+                &signature,
+                &messages,
+                &public_keys
+            )),
+            VerificationError::InvalidSignature
+        );
+        assert_eq!(
+            err(try_fast_aggregate_verify(&signature, messages[0], &[])),
+            VerificationError::Empty
+        );
+        assert_eq!(
+            err(try_fast_aggregate_verify(
+                &signature,
+                messages[0],
+                &zero_keys
+            )),
+            VerificationError::IdentityPublicKey(1)
+        );
+        assert_eq!(
+            err(try_fast_aggregate_verify(
+                &signature,
+                messages[0],
+                &public_keys
+            )),
+            VerificationError::InvalidSignature
+        );
+        assert!(try_verify_messages_with_dst(&signature, &messages, &public_keys, CSUITE).is_ok());
+        assert!(matches!(
+            try_verify_messages_with_dst(&signature, &messages, &public_keys, b""),
+            Err(Error::InvalidDst)
+        ));
+        assert_eq!(
+            err(try_verify_messages_with_dst(
+                &signature,
+                &messages,
+                &public_keys,
+                AUG_CSUITE
+            )),
+            VerificationError::InvalidSignature
+        );
+
+        let proof = private_keys[0].pop_prove();
+        assert!(try_verify_pop(&proof, &public_keys[0]).is_ok());
+        assert_eq!(
+            err(try_verify_pop(&proof, &public_keys[1])),
+            VerificationError::InvalidSignature
+        );
+        assert!(matches!(
+            try_verify_pop(&proof, &PublicKey(G1Projective::identity())),
+            Err(Error::InvalidPublicKey)
+        ));
+    }
+
+    #[test]
+    fn test_zero_key() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        // zero private keys can not be constructed
+        assert!(PrivateKey::try_from(Scalar::zero()).is_err());
+        assert!(PrivateKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(PrivateKey::from_string("0").is_err());
+
+        // and the matching public key fails KeyValidate
+        let zero_key = PublicKey(G1Projective::identity());
+        assert!(zero_key.validate().is_err());
+        assert!(ValidatedPublicKey::from_bytes(&zero_key.as_bytes()).is_err());
+
+        let num_messages = 10;
+
+        // generate private keys
+        let private_keys: Vec<_> = (0..num_messages - 1)
+            .map(|_| PrivateKey::generate(&mut rng))
+            .collect();
 
         // generate messages
         let messages: Vec<Vec<u8>> = (0..num_messages)
             .map(|_| (0..64).map(|_| rng.gen()).collect())
             .collect();
 
-        // sign messages
+        // sign messages, the zero key would contribute the identity
         let sigs = messages
             .iter()
             .zip(&private_keys)
             .map(|(message, pk)| pk.sign(message))
             .collect::<Vec<Signature>>();
 
-        let aggregated_signature = aggregate(&sigs).expect("failed to aggregate");
+        let aggregated_signature = aggregate(&sigs).unwrap().validate().unwrap();
 
         let hashes = messages
             .iter()
             .map(|message| hash(message))
             .collect::<Vec<_>>();
-        let public_keys = private_keys
+        let mut public_keys = private_keys
             .iter()
-            .map(|pk| pk.public_key())
+            .map(|pk| pk.public_key().validate().unwrap())
             .collect::<Vec<_>>();
+        public_keys.push(ValidatedPublicKey::new_unchecked(zero_key));
 
         assert!(
             !verify(&aggregated_signature, &hashes, &public_keys),
@@ -494,18 +1810,80 @@ mod tests {
         ));
 
         // single message is rejected
-        let signature = zero_key.sign(&messages[0]);
-
-        assert!(!zero_key.public_key().verify(signature, &messages[0]));
+        let signature = Signature::from(G2Projective::identity());
 
-        let aggregated_signature = aggregate(&[signature][..]).expect("failed to aggregate");
+        assert!(!zero_key.verify(signature, messages[0]));
         assert!(!verify_messages(
-            &aggregated_signature,
+            &signature.validate().unwrap(),
             &messages[..1],
-            &[zero_key.public_key()][..],
+            &[ValidatedPublicKey::new_unchecked(zero_key)],
         ));
     }
 
+    #[test]
+    fn test_validation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let sk = PrivateKey::generate(&mut rng);
+        let pk = sk.public_key();
+        let sig = sk.sign(b"hello");
+
+        let validated_pk = pk.validate().unwrap();
+        let validated_sig = sig.validate().unwrap();
+        assert_eq!(PublicKey::from(validated_pk), pk);
+        assert_eq!(Signature::from(validated_sig), sig);
+        assert_eq!(
+            ValidatedPublicKey::from_bytes(&pk.as_bytes()).unwrap(),
+            validated_pk
+        );
+        assert_eq!(
+            ValidatedSignature::from_bytes(&sig.as_bytes()).unwrap(),
+            validated_sig
+        );
+        assert!(verify_messages(
+            &validated_sig,
+            &[b"hello"],
+            &[validated_pk]
+        ));
+
+        // points outside of the prime-order subgroup are rejected
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let torsion_pk = loop {
+            let mut raw = [0u8; 48];
+            rng.fill_bytes(&mut raw);
+            raw[0] = (raw[0] & 0x1f) | 0x80;
+            let point = G1Affine::from_compressed_unchecked(&raw);
+            if point.is_some().into() {
+                let point = point.unwrap();
+                if !bool::from(point.is_torsion_free()) {
+                    break PublicKey(point.into());
+                }
+            }
+        };
+        assert!(matches!(
+            torsion_pk.validate(),
+            Err(Error::InvalidPublicKey)
+        ));
+        assert!(!torsion_pk.verify(sig, b"hello"));
+
+        let torsion_sig = loop {
+            let mut raw = [0u8; 96];
+            rng.fill_bytes(&mut raw);
+            raw[0] = (raw[0] & 0x1f) | 0x80;
+            let point = G2Affine::from_compressed_unchecked(&raw);
+            if point.is_some().into() {
+                let point = point.unwrap();
+                if !bool::from(point.is_torsion_free()) {
+                    break Signature::from(point);
+                }
+            }
+        };
+        assert!(matches!(
+            torsion_sig.validate(),
+            Err(Error::InvalidSignature)
+        ));
+        assert!(!pk.verify(torsion_sig, b"hello"));
+    }
+
     #[test]
     fn test_bytes_roundtrip() {
         let mut rng = ChaCha8Rng::seed_from_u64(12);
@@ -517,6 +1895,28 @@ mod tests {
         let signature_bytes = signature.as_bytes();
         assert_eq!(signature_bytes.len(), 96);
         assert_eq!(Signature::from_bytes(&signature_bytes).unwrap(), signature);
+        assert_eq!(&signature.to_compressed()[..], &signature_bytes[..]);
+        assert_eq!(
+            Signature::from_compressed(&signature.to_compressed()).unwrap(),
+            signature
+        );
+
+        let raw = signature.to_uncompressed();
+        assert_eq!(raw.len(), 192);
+        assert_eq!(Signature::from_uncompressed(&raw).unwrap(), signature);
+        assert_eq!(
+            Signature::from_uncompressed_unchecked(&raw).unwrap(),
+            signature
+        );
+
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(&signature_bytes)));
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), signature);
+        let encoded = bincode::serialize(&signature).unwrap();
+        assert_eq!(
+            bincode::deserialize::<ValidatedSignature>(&encoded).unwrap(),
+            signature.validate().unwrap()
+        );
     }
 
     base64_serde_type!(Base64Standard, STANDARD);
@@ -564,15 +1964,6 @@ mod tests {
         G1Projective::hash_to_curve(msg, suite, &[])
     }
 
-    #[cfg(feature = "pairing")]
-    fn hash_to_g2(msg: &[u8], suite: &[u8]) -> G2Projective {
-        <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg, suite)
-    }
-    #[cfg(feature = "blst")]
-    fn hash_to_g2(msg: &[u8], suite: &[u8]) -> G2Projective {
-        G2Projective::hash_to_curve(msg, suite, &[])
-    }
-
     #[test]
     fn test_vectors() {
         let cases: Cases =